@@ -0,0 +1,268 @@
+//! Benchmarks comparing `Vec0<i32>` against `std::vec::Vec<i32>`.
+//!
+//! Run with: cargo bench --bench vec_bench
+//!
+//! Both vectors use the global allocator and a doubling growth strategy, so
+//! most operations should land in the same ballpark. Differences you can
+//! expect to see:
+//!
+//! - `push`: `Vec0` starts at capacity 1 while `Vec` starts at 4 for small
+//!   elements, so `Vec0` performs a couple of extra reallocations early on.
+//! - `insert(0, x)` / `remove(0)`: both are O(n) because every element is
+//!   shifted with a `memmove`. The curves should grow at the same rate.
+//! - `clone`: `Vec0::clone` pushes elements one by one, while `Vec` copies
+//!   `Copy` data with a single `memcpy`. Expect `Vec0` to be noticeably slower.
+//! - `extend`: `Vec0` pushes each item, `Vec` reserves using the iterator's
+//!   `size_hint` first.
+//! - `sort`: `Vec0` derefs to `[T]`, so it runs the exact same slice sort.
+//! - `extend_from_copy_slice`: `Vec0::extend_from_copy_slice` reserves once
+//!   and does a single `memcpy`, like `Vec::extend_from_slice`. Pushing the
+//!   same elements through `extend` checks capacity and writes one element
+//!   per iteration, so expect the bulk copy to win clearly at larger N.
+//!
+//! None of the operations should show quadratic growth between N = 100,
+//! 1000 and 10000, except `insert(0, x)` / `remove(0)` repeated N times,
+//! which are O(n²) for both implementations by design.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rustlib::vec::Vec0;
+
+const SIZES: [usize; 3] = [100, 1000, 10000];
+
+fn filled_vec0(n: usize) -> Vec0<i32> {
+    let mut v = Vec0::new();
+    for i in 0..n as i32 {
+        v.push(i);
+    }
+    v
+}
+
+fn filled_vec(n: usize) -> Vec<i32> {
+    (0..n as i32).collect()
+}
+
+/// Descending data so `sort` has actual work to do.
+fn reversed_vec0(n: usize) -> Vec0<i32> {
+    let mut v = Vec0::new();
+    for i in (0..n as i32).rev() {
+        v.push(i);
+    }
+    v
+}
+
+fn bench_push(c: &mut Criterion) {
+    let mut group = c.benchmark_group("push");
+    for n in SIZES {
+        group.bench_with_input(BenchmarkId::new("Vec0", n), &n, |b, &n| {
+            b.iter(|| {
+                let mut v = Vec0::new();
+                for i in 0..n as i32 {
+                    v.push(black_box(i));
+                }
+                v
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("Vec", n), &n, |b, &n| {
+            b.iter(|| {
+                let mut v = Vec::new();
+                for i in 0..n as i32 {
+                    v.push(black_box(i));
+                }
+                v
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_pop(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pop");
+    for n in SIZES {
+        group.bench_with_input(BenchmarkId::new("Vec0", n), &n, |b, &n| {
+            b.iter_batched(
+                || filled_vec0(n),
+                |mut v| {
+                    while let Some(x) = v.pop() {
+                        black_box(x);
+                    }
+                    v
+                },
+                criterion::BatchSize::SmallInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("Vec", n), &n, |b, &n| {
+            b.iter_batched(
+                || filled_vec(n),
+                |mut v| {
+                    while let Some(x) = v.pop() {
+                        black_box(x);
+                    }
+                    v
+                },
+                criterion::BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn bench_insert_front(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insert(0, x)");
+    for n in SIZES {
+        group.bench_with_input(BenchmarkId::new("Vec0", n), &n, |b, &n| {
+            b.iter(|| {
+                let mut v = Vec0::new();
+                for i in 0..n as i32 {
+                    v.insert(0, black_box(i));
+                }
+                v
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("Vec", n), &n, |b, &n| {
+            b.iter(|| {
+                let mut v = Vec::new();
+                for i in 0..n as i32 {
+                    v.insert(0, black_box(i));
+                }
+                v
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_remove_front(c: &mut Criterion) {
+    let mut group = c.benchmark_group("remove(0)");
+    for n in SIZES {
+        group.bench_with_input(BenchmarkId::new("Vec0", n), &n, |b, &n| {
+            b.iter_batched(
+                || filled_vec0(n),
+                |mut v| {
+                    while !v.is_empty() {
+                        black_box(v.remove(0));
+                    }
+                    v
+                },
+                criterion::BatchSize::SmallInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("Vec", n), &n, |b, &n| {
+            b.iter_batched(
+                || filled_vec(n),
+                |mut v| {
+                    while !v.is_empty() {
+                        black_box(v.remove(0));
+                    }
+                    v
+                },
+                criterion::BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn bench_clone(c: &mut Criterion) {
+    let mut group = c.benchmark_group("clone");
+    for n in SIZES {
+        let v0 = filled_vec0(n);
+        group.bench_with_input(BenchmarkId::new("Vec0", n), &v0, |b, v| {
+            b.iter(|| black_box(v).clone())
+        });
+        let v = filled_vec(n);
+        group.bench_with_input(BenchmarkId::new("Vec", n), &v, |b, v| {
+            b.iter(|| black_box(v).clone())
+        });
+    }
+    group.finish();
+}
+
+fn bench_extend(c: &mut Criterion) {
+    let mut group = c.benchmark_group("extend");
+    for n in SIZES {
+        group.bench_with_input(BenchmarkId::new("Vec0", n), &n, |b, &n| {
+            b.iter(|| {
+                let mut v = Vec0::new();
+                v.extend(black_box(0..n as i32));
+                v
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("Vec", n), &n, |b, &n| {
+            b.iter(|| {
+                let mut v = Vec::new();
+                v.extend(black_box(0..n as i32));
+                v
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_extend_from_copy_slice(c: &mut Criterion) {
+    let mut group = c.benchmark_group("extend_from_copy_slice");
+    for n in SIZES {
+        let src = filled_vec(n);
+        group.bench_with_input(BenchmarkId::new("Vec0", n), &src, |b, src| {
+            b.iter(|| {
+                let mut v = Vec0::new();
+                v.extend_from_copy_slice(black_box(src));
+                v
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("Vec0 extend", n), &src, |b, src| {
+            b.iter(|| {
+                let mut v = Vec0::new();
+                v.extend(black_box(src).iter().copied());
+                v
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("Vec", n), &src, |b, src| {
+            b.iter(|| {
+                let mut v = Vec::new();
+                v.extend_from_slice(black_box(src));
+                v
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_sort(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sort");
+    for n in SIZES {
+        group.bench_with_input(BenchmarkId::new("Vec0", n), &n, |b, &n| {
+            b.iter_batched(
+                || reversed_vec0(n),
+                |mut v| {
+                    v.sort();
+                    v
+                },
+                criterion::BatchSize::SmallInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("Vec", n), &n, |b, &n| {
+            b.iter_batched(
+                || filled_vec(n).into_iter().rev().collect::<Vec<_>>(),
+                |mut v| {
+                    v.sort();
+                    v
+                },
+                criterion::BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_push,
+    bench_pop,
+    bench_insert_front,
+    bench_remove_front,
+    bench_clone,
+    bench_extend,
+    bench_extend_from_copy_slice,
+    bench_sort
+);
+criterion_main!(benches);
@@ -0,0 +1,205 @@
+//! Chapter 3 (advanced): Box - Worked Examples
+//!
+//! Goes beyond the basics in 03_box.rs: pinning, leaking and recursive types.
+//! Every demo is complete, read through them and run:
+//! cargo run --example box_advanced
+
+#![allow(unused)]
+
+#[macro_use]
+mod common;
+
+use rustlib::r#box::Box0;
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::sync::OnceLock;
+
+// ============================================================================
+// 1. Pin - self-referential structs
+// ============================================================================
+
+// A struct that stores a pointer to one of its own fields. If it were moved,
+// `data` would live at a new address but `data_ptr` would still point to the
+// old one. PhantomPinned opts out of Unpin, so once pinned it can't be moved.
+struct SelfReferential {
+    data: String,
+    data_ptr: *const String,
+    _pin: PhantomPinned,
+}
+
+impl SelfReferential {
+    fn new(data: &str) -> Pin<Box0<SelfReferential>> {
+        let mut pinned = Box0::pin(SelfReferential {
+            data: String::from(data),
+            data_ptr: std::ptr::null(),
+            _pin: PhantomPinned,
+        });
+
+        // The value is on the heap now and will stay there,
+        // so it's safe to point at our own field.
+        let data_ptr = &pinned.data as *const String;
+        unsafe {
+            pinned.as_mut().get_unchecked_mut().data_ptr = data_ptr;
+        }
+        pinned
+    }
+
+    fn data_via_ptr(&self) -> &str {
+        unsafe { &*self.data_ptr }
+    }
+}
+
+fn _01_pin_self_referential() {
+    let pinned = SelfReferential::new("hello");
+
+    // Moving the Pin<Box0<_>> only moves the 8-byte pointer on the stack.
+    // The heap value (and the address data_ptr points to) doesn't change.
+    let moved = pinned;
+    assert_eq!(moved.data_via_ptr(), "hello");
+    assert_eq!(moved.data_ptr, &moved.data as *const String);
+
+    // This wouldn't compile: SelfReferential is !Unpin, so we can't get
+    // a `&mut SelfReferential` and std::mem::swap it somewhere else.
+    // let r: &mut SelfReferential = Pin::get_mut(moved.as_mut());
+}
+
+// ============================================================================
+// 2. leak - 'static references
+// ============================================================================
+
+#[derive(Debug)]
+struct Config {
+    name: String,
+    max_connections: usize,
+}
+
+static CONFIG: OnceLock<&'static Config> = OnceLock::new();
+
+// A global singleton built at runtime. leak() turns the heap allocation into a
+// &'static reference: it lives until the process exits, which is exactly what
+// a program-wide config needs. The memory is reclaimed by the OS at exit.
+fn config() -> &'static Config {
+    CONFIG.get_or_init(|| {
+        let boxed = Box0::new(Config {
+            name: String::from("libr0"),
+            max_connections: 16,
+        });
+        boxed.leak()
+    })
+}
+
+fn _02_leak_static_singleton() {
+    let first = config();
+    let second = config();
+
+    // Both calls return the same leaked allocation
+    assert!(std::ptr::eq(first, second));
+    assert_eq!(first.name, "libr0");
+    assert_eq!(second.max_connections, 16);
+
+    // When to use leak:
+    // - values that must live for the rest of the program (configs, interners)
+    // - APIs that demand &'static (e.g. thread::spawn without scoped threads)
+    // When not to: anything created repeatedly - every call leaks more memory.
+}
+
+// ============================================================================
+// 3. Recursive types - a binary search tree
+// ============================================================================
+
+// Why does this need Box0? Without it:
+//
+//     enum Tree<T> {
+//         Leaf,
+//         Node(T, Tree<T>, Tree<T>),
+//     }
+//
+// is rejected with:
+//
+//     error[E0072]: recursive type `Tree` has infinite size
+//
+// To lay out a Node the compiler needs size_of::<Tree<T>>(), which contains
+// two more Tree<T>s, each containing two more... the size never converges.
+// Box0<Tree<T>> is always a single pointer (8 bytes), so the recursion stops.
+#[derive(Debug)]
+enum Tree<T> {
+    Leaf,
+    Node(T, Box0<Tree<T>>, Box0<Tree<T>>),
+}
+
+impl<T: Ord> Tree<T> {
+    fn new() -> Tree<T> {
+        Tree::Leaf
+    }
+
+    fn insert(&mut self, value: T) {
+        match self {
+            Tree::Leaf => {
+                *self = Tree::Node(value, Box0::new(Tree::Leaf), Box0::new(Tree::Leaf));
+            }
+            Tree::Node(current, left, right) => {
+                if value < *current {
+                    left.insert(value); // Deref coercion: &mut Box0<Tree> -> &mut Tree
+                } else if value > *current {
+                    right.insert(value);
+                }
+                // Equal values are already in the tree
+            }
+        }
+    }
+
+    fn contains(&self, value: &T) -> bool {
+        match self {
+            Tree::Leaf => false,
+            Tree::Node(current, left, right) => {
+                if value < current {
+                    left.contains(value)
+                } else if value > current {
+                    right.contains(value)
+                } else {
+                    true
+                }
+            }
+        }
+    }
+
+    fn in_order<'a>(&'a self, out: &mut Vec<&'a T>) {
+        if let Tree::Node(value, left, right) = self {
+            left.in_order(out);
+            out.push(value);
+            right.in_order(out);
+        }
+    }
+}
+
+fn _03_recursive_tree() {
+    let mut tree = Tree::new();
+    for value in [50, 30, 70, 20, 40, 60, 80, 30] {
+        tree.insert(value);
+    }
+
+    assert!(tree.contains(&40));
+    assert!(tree.contains(&80));
+    assert!(!tree.contains(&45));
+
+    // In-order traversal of a BST visits values in sorted order
+    let mut sorted = Vec::new();
+    tree.in_order(&mut sorted);
+    assert_eq!(sorted, vec![&20, &30, &40, &50, &60, &70, &80]);
+
+    // The enum itself stays small: tag + T + two pointers
+    assert_eq!(std::mem::size_of::<Tree<i32>>(), 24);
+}
+
+// ============================================================================
+// Main
+// ============================================================================
+
+fn main() {
+    run_all![
+        "Box0 (advanced)",
+        _01_pin_self_referential,
+        _02_leak_static_singleton,
+        _03_recursive_tree,
+    ];
+}
@@ -0,0 +1,215 @@
+//! Rc0<RefCell0<T>> - Worked Example: a directed graph
+//!
+//! Rc0 gives shared ownership, RefCell0 gives mutation through a shared
+//! reference. Together they let many edges point at the same node and still
+//! modify it. This file shows the power of the pattern and its main hazard:
+//! reference cycles that are never freed.
+//! Run with: cargo run --example rc_refcell_graph
+
+#![allow(unused)]
+
+#[macro_use]
+mod common;
+
+use rustlib::rc::{Rc0, Weak0};
+use rustlib::refcell::RefCell0;
+use rustlib::vec::Vec0;
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+
+// ============================================================================
+// Graph building blocks
+// ============================================================================
+
+struct Node<T> {
+    value: T,
+    edges: Vec0<Rc0<RefCell0<Node<T>>>>,
+}
+
+type NodeRef<T> = Rc0<RefCell0<Node<T>>>;
+
+fn add_node<T>(value: T) -> NodeRef<T> {
+    Rc0::new(RefCell0::new(Node {
+        value,
+        edges: Vec0::new(),
+    }))
+}
+
+// `from` is only borrowed, yet we push into its edges:
+// RefCell0 moves the borrow check from compile time to runtime.
+fn add_edge<T>(from: &NodeRef<T>, to: &NodeRef<T>) {
+    from.borrow_mut().edges.push(to.clone());
+}
+
+// Two Rc0s are the same node if they point to the same allocation
+fn node_id<T>(node: &NodeRef<T>) -> *const RefCell0<Node<T>> {
+    &**node as *const RefCell0<Node<T>>
+}
+
+fn dfs<T: Clone>(start: &NodeRef<T>) -> Vec<T> {
+    let mut visited = HashSet::new();
+    let mut order = Vec::new();
+    let mut stack = vec![start.clone()];
+
+    while let Some(node) = stack.pop() {
+        if !visited.insert(node_id(&node)) {
+            continue; // Already seen, also what stops us looping on cycles
+        }
+        let node = node.borrow();
+        order.push(node.value.clone());
+        // Push in reverse so the first edge is visited first
+        for next in node.edges.iter().rev() {
+            stack.push(next.clone());
+        }
+    }
+    order
+}
+
+fn bfs<T: Clone>(start: &NodeRef<T>) -> Vec<T> {
+    let mut visited = HashSet::new();
+    let mut order = Vec::new();
+    let mut queue = VecDeque::new();
+
+    visited.insert(node_id(start));
+    queue.push_back(start.clone());
+
+    while let Some(node) = queue.pop_front() {
+        let node = node.borrow();
+        order.push(node.value.clone());
+        for next in node.edges.iter() {
+            if visited.insert(node_id(next)) {
+                queue.push_back(next.clone());
+            }
+        }
+    }
+    order
+}
+
+// ============================================================================
+// The power: shared, mutable nodes
+// ============================================================================
+
+fn _01_build_and_traverse() {
+    //      a
+    //     / \
+    //    b   c
+    //     \ /
+    //      d      (d is shared by b and c)
+    let a = add_node("a");
+    let b = add_node("b");
+    let c = add_node("c");
+    let d = add_node("d");
+    add_edge(&a, &b);
+    add_edge(&a, &c);
+    add_edge(&b, &d);
+    add_edge(&c, &d);
+
+    assert_eq!(dfs(&a), vec!["a", "b", "d", "c"]);
+    assert_eq!(bfs(&a), vec!["a", "b", "c", "d"]);
+
+    // d is owned by our local handle plus the edges from b and c
+    assert_eq!(Rc0::strong_count(&d), 3);
+}
+
+fn _02_shared_mutation() {
+    let a = add_node(1);
+    let b = add_node(2);
+    let shared = add_node(10);
+    add_edge(&a, &shared);
+    add_edge(&b, &shared);
+
+    // Mutate through a's edge...
+    a.borrow().edges[0].borrow_mut().value += 5;
+
+    // ...and the change is visible through b's edge: it's the same node
+    assert_eq!(b.borrow().edges[0].borrow().value, 15);
+    assert_eq!(shared.borrow().value, 15);
+}
+
+// ============================================================================
+// The hazard: reference cycles leak
+// ============================================================================
+
+fn _03_cycle_leaks() {
+    // The Arc lets us observe whether the node values were dropped
+    let drop_checker = Arc::new(());
+
+    let weak_a: Weak0<RefCell0<Node<Arc<()>>>>;
+    {
+        let a = add_node(drop_checker.clone());
+        let b = add_node(drop_checker.clone());
+        add_edge(&a, &b);
+        add_edge(&b, &a); // a -> b -> a
+        weak_a = Rc0::downgrade(&a);
+
+        assert_eq!(Arc::strong_count(&drop_checker), 3);
+    } // a and b go out of scope here...
+
+    // ...but each node is still owned by the other's edge list.
+    // Strong counts never reach 0, so neither value is dropped.
+    assert_eq!(Arc::strong_count(&drop_checker), 3);
+    assert!(weak_a.upgrade().is_some());
+
+    // The only fix after the fact is breaking the cycle by hand
+    if let Some(a) = weak_a.upgrade() {
+        a.borrow_mut().edges.clear();
+    }
+    assert_eq!(Arc::strong_count(&drop_checker), 1);
+    assert!(weak_a.upgrade().is_none());
+}
+
+// ============================================================================
+// The fix: Weak0 for back-edges
+// ============================================================================
+
+// In a tree, children are owned (Rc0) and the parent link is a back-edge.
+// Making it a Weak0 means it doesn't count towards ownership, so there is
+// no cycle of strong references.
+struct TreeNode {
+    value: Arc<()>,
+    children: Vec0<Rc0<RefCell0<TreeNode>>>,
+    parent: Option<Weak0<RefCell0<TreeNode>>>,
+}
+
+fn _04_weak_back_edges() {
+    let drop_checker = Arc::new(());
+    {
+        let parent = Rc0::new(RefCell0::new(TreeNode {
+            value: drop_checker.clone(),
+            children: Vec0::new(),
+            parent: None,
+        }));
+        let child = Rc0::new(RefCell0::new(TreeNode {
+            value: drop_checker.clone(),
+            children: Vec0::new(),
+            parent: Some(Rc0::downgrade(&parent)),
+        }));
+        parent.borrow_mut().children.push(child.clone());
+
+        // The child can still reach its parent, but only via upgrade()
+        let up = child.borrow().parent.as_ref().unwrap().upgrade();
+        assert!(up.is_some());
+        drop(up);
+
+        assert_eq!(Rc0::strong_count(&parent), 1); // Weak0 doesn't count
+        assert_eq!(Rc0::weak_count(&parent), 1);
+        assert_eq!(Arc::strong_count(&drop_checker), 3);
+    }
+
+    // Everything was freed: no leak
+    assert_eq!(Arc::strong_count(&drop_checker), 1);
+}
+
+// ============================================================================
+// Main
+// ============================================================================
+
+fn main() {
+    run_all![
+        "Rc0<RefCell0<T>> graph",
+        _01_build_and_traverse,
+        _02_shared_mutation,
+        _03_cycle_leaks,
+        _04_weak_back_edges,
+    ];
+}
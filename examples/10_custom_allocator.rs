@@ -0,0 +1,239 @@
+//! Chapter 4 (advanced): Vec - Worked Example: a bump allocator
+//!
+//! Vec0 gets its memory from `std::alloc::alloc`, which forwards to whatever
+//! `#[global_allocator]` the program installed. Here we install our own:
+//! a bump (arena) allocator that hands out slices of a fixed byte array and
+//! never frees them one by one. Everything is released at once when the
+//! arena itself is dropped.
+//! Run with: cargo run --example custom_allocator
+
+#![allow(unused)]
+
+#[macro_use]
+mod common;
+
+use rustlib::vec::Vec0;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::{Cell, UnsafeCell};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// ============================================================================
+// The bump allocator
+// ============================================================================
+
+// A fixed-size byte array plus an index that only ever moves forward:
+//
+//      buf:  [ a a a a | b b · · | c c c c c c | · · · · · · · · ]
+//                                               ^
+//                                              next
+//
+// Allocating rounds `next` up to the requested alignment and bumps it past
+// the new block. Deallocating does nothing: there is no free list to put the
+// block back into, and that's the whole trick. Allocation is a few additions,
+// and the cost of freeing is paid once, for everything, when the arena dies.
+struct BumpAllocator<const N: usize> {
+    buf: UnsafeCell<[u8; N]>,
+    next: Cell<usize>,
+}
+
+// Bytes handed back by dropped arenas, so the demos can observe the bulk free
+static RELEASED: AtomicUsize = AtomicUsize::new(0);
+
+impl<const N: usize> BumpAllocator<N> {
+    fn new() -> Self {
+        BumpAllocator {
+            buf: UnsafeCell::new([0; N]),
+            next: Cell::new(0),
+        }
+    }
+
+    fn used(&self) -> usize {
+        self.next.get()
+    }
+
+    fn base(&self) -> usize {
+        self.buf.get() as usize
+    }
+}
+
+unsafe impl<const N: usize> GlobalAlloc for BumpAllocator<N> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // The array itself is only 1-aligned, so align the address, not the index
+        let start = self.base() + self.next.get();
+        let aligned = (start + layout.align() - 1) & !(layout.align() - 1);
+        let end = aligned - self.base() + layout.size();
+
+        if end > N {
+            return std::ptr::null_mut(); // Out of space: the caller reports the error
+        }
+        self.next.set(end);
+        aligned as *mut u8
+    }
+
+    unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {
+        // No-op: the block stays used until the whole arena goes away
+    }
+}
+
+impl<const N: usize> Drop for BumpAllocator<N> {
+    fn drop(&mut self) {
+        // One step releases every block, however many were handed out
+        RELEASED.fetch_add(self.next.get(), Ordering::SeqCst);
+    }
+}
+
+// ============================================================================
+// Plugging it in as the global allocator
+// ============================================================================
+
+// Only one `#[global_allocator]` can exist, and it must live forever, while
+// our arenas are ordinary values that come and go. So the global allocator is
+// a small dispatcher: if the current thread has an arena switched on, it
+// allocates from it, otherwise it falls back to the system allocator.
+trait Arena: GlobalAlloc {
+    fn owns(&self, ptr: *mut u8) -> bool;
+}
+
+impl<const N: usize> Arena for BumpAllocator<N> {
+    fn owns(&self, ptr: *mut u8) -> bool {
+        (self.base()..self.base() + N).contains(&(ptr as usize))
+    }
+}
+
+thread_local! {
+    // `const` and no destructor: safe to touch from inside the allocator
+    static ACTIVE: Cell<Option<*const dyn Arena>> = const { Cell::new(None) };
+}
+
+struct ArenaOrSystem;
+
+unsafe impl GlobalAlloc for ArenaOrSystem {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        match ACTIVE.with(Cell::get) {
+            Some(arena) => (*arena).alloc(layout),
+            None => System.alloc(layout),
+        }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if let Some(arena) = ACTIVE.with(Cell::get) {
+            if (*arena).owns(ptr) {
+                return (*arena).dealloc(ptr, layout);
+            }
+        }
+        System.dealloc(ptr, layout)
+    }
+
+    // The default `realloc` (alloc new, copy, dealloc old) goes through the
+    // two methods above, so a Vec0 growing inside an arena stays in the arena.
+}
+
+#[global_allocator]
+static GLOBAL: ArenaOrSystem = ArenaOrSystem;
+
+// Runs `f` with every allocation on this thread served by `arena`.
+//
+// Nothing allocated inside may outlive the call: once the arena is switched
+// off, freeing its blocks would reach the system allocator. That's why the
+// demos below only return plain numbers out of the closure.
+fn with_arena<A: Arena + 'static, R>(arena: &A, f: impl FnOnce() -> R) -> R {
+    // Restores the previous arena even if `f` panics
+    struct Restore(Option<*const dyn Arena>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            ACTIVE.with(|active| active.set(self.0));
+        }
+    }
+
+    let arena: *const dyn Arena = arena;
+    let _restore = Restore(ACTIVE.with(|active| active.replace(Some(arena))));
+    f()
+}
+
+// ============================================================================
+// 1. Bumping: allocation is just moving an index
+// ============================================================================
+
+fn _01_bump_in_order() {
+    let arena = BumpAllocator::<64>::new();
+    unsafe {
+        let a = arena.alloc(Layout::new::<u8>());
+        let b = arena.alloc(Layout::new::<u64>());
+
+        // Blocks come out in address order, b rounded up to 8-byte alignment
+        assert!(b > a);
+        assert_eq!(b as usize % 8, 0);
+        let used = arena.used();
+
+        // dealloc is a no-op: the space is not reused
+        arena.dealloc(b, Layout::new::<u64>());
+        assert_eq!(arena.used(), used);
+
+        // Running out returns null instead of reusing freed space
+        assert!(arena.alloc(Layout::array::<u8>(128).unwrap()).is_null());
+    }
+}
+
+// ============================================================================
+// 2. Vec0 on top of the arena
+// ============================================================================
+
+fn _02_vec0_in_arena() {
+    let arena = BumpAllocator::<4096>::new();
+
+    let (in_arena, sum, used_before_drop, used_after_drop) = with_arena(&arena, || {
+        let mut v = Vec0::new();
+        for i in 1..=100u32 {
+            v.push(i); // Each grow is a realloc: a new block, the old one abandoned
+        }
+        let in_arena = arena.owns(v.as_slice().as_ptr() as *mut u8);
+        let sum: u32 = v.iter().sum();
+
+        let used_before_drop = arena.used();
+        drop(v); // Vec0's dealloc reaches our no-op
+        (in_arena, sum, used_before_drop, arena.used())
+    });
+
+    assert!(in_arena);
+    assert_eq!(sum, 5050);
+    // Capacities 1, 2, 4, ..., 128 were all allocated and none was reclaimed:
+    // far more than the 400 bytes the final 100 elements need
+    assert!(used_before_drop > 100 * 4);
+    assert_eq!(used_after_drop, used_before_drop);
+}
+
+// ============================================================================
+// 3. Dropping the arena frees everything at once
+// ============================================================================
+
+fn _03_drop_frees_all() {
+    let released_before = RELEASED.load(Ordering::SeqCst);
+    let used;
+    {
+        let arena = BumpAllocator::<4096>::new();
+        used = with_arena(&arena, || {
+            // Many small vectors, never freed individually
+            for n in 0..10 {
+                let v: Vec0<u64> = (0..n).collect();
+                std::mem::forget(v);
+            }
+            arena.used()
+        });
+        assert!(used > 0);
+    } // arena dropped here
+
+    assert_eq!(RELEASED.load(Ordering::SeqCst) - released_before, used);
+}
+
+// ============================================================================
+// Main
+// ============================================================================
+
+fn main() {
+    run_all![
+        "Custom allocator",
+        _01_bump_in_order,
+        _02_vec0_in_arena,
+        _03_drop_frees_all,
+    ];
+}
@@ -0,0 +1,251 @@
+//! Chapter 4 (advanced): Vec - Worked Example: watching the allocator
+//!
+//! Every byte Vec0 owns comes from `alloc`, `realloc` and `dealloc` calls on
+//! the global allocator. This file installs a tracing shim in front of the
+//! system allocator and records each call with its `(ptr, size, align)`, so
+//! the doubling growth, the reallocs and the final free become visible.
+//! Run with: cargo run --example allocator_internals
+
+#![allow(unused)]
+
+#[macro_use]
+mod common;
+
+use rustlib::vec::Vec0;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::{Cell, RefCell};
+
+// ============================================================================
+// The tracing allocator
+// ============================================================================
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Event {
+    Alloc {
+        ptr: usize,
+        size: usize,
+        align: usize,
+    },
+    Realloc {
+        old_ptr: usize,
+        new_ptr: usize,
+        old_size: usize,
+        new_size: usize,
+        align: usize,
+    },
+    Dealloc {
+        ptr: usize,
+        size: usize,
+        align: usize,
+    },
+}
+
+// The allocator can't allocate to store what it sees, so events go into a
+// fixed-size buffer. Thread-local, so only the thread being traced records.
+const MAX_EVENTS: usize = 64;
+
+thread_local! {
+    static TRACING: Cell<bool> = const { Cell::new(false) };
+    static EVENTS: RefCell<([Option<Event>; MAX_EVENTS], usize)> =
+        const { RefCell::new(([None; MAX_EVENTS], 0)) };
+}
+
+fn record(event: Event) {
+    if TRACING.with(Cell::get) {
+        EVENTS.with(|events| {
+            let (buf, len) = &mut *events.borrow_mut();
+            if *len < MAX_EVENTS {
+                buf[*len] = Some(event);
+                *len += 1;
+            }
+        });
+    }
+}
+
+// Forwards every call to the system allocator, writing down what happened
+struct TracingAllocator;
+
+unsafe impl GlobalAlloc for TracingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        record(Event::Alloc {
+            ptr: ptr as usize,
+            size: layout.size(),
+            align: layout.align(),
+        });
+        ptr
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        record(Event::Realloc {
+            old_ptr: ptr as usize,
+            new_ptr: new_ptr as usize,
+            old_size: layout.size(),
+            new_size,
+            align: layout.align(),
+        });
+        new_ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(Event::Dealloc {
+            ptr: ptr as usize,
+            size: layout.size(),
+            align: layout.align(),
+        });
+    }
+}
+
+#[global_allocator]
+static GLOBAL: TracingAllocator = TracingAllocator;
+
+// Runs `f` with tracing on and returns every allocator call it made.
+// The returned Vec is built after tracing is switched off, so it isn't in it.
+fn trace<R>(f: impl FnOnce() -> R) -> Vec<Event> {
+    EVENTS.with(|events| events.borrow_mut().1 = 0);
+    TRACING.with(|tracing| tracing.set(true));
+    let result = f();
+    TRACING.with(|tracing| tracing.set(false));
+    drop(result);
+
+    EVENTS.with(|events| {
+        let (buf, len) = &*events.borrow();
+        buf[..*len].iter().flatten().copied().collect()
+    })
+}
+
+fn print_trace(events: &[Event]) {
+    println!();
+    for event in events {
+        match event {
+            Event::Alloc { ptr, size, align } => {
+                println!(
+                    "    alloc    {:#x}  {:>3} bytes, align {}",
+                    ptr, size, align
+                )
+            }
+            Event::Realloc {
+                old_ptr,
+                new_ptr,
+                old_size,
+                new_size,
+                align,
+            } => println!(
+                "    realloc  {:#x}  {:>3} -> {:>3} bytes, align {}{}",
+                old_ptr,
+                old_size,
+                new_size,
+                align,
+                if old_ptr == new_ptr {
+                    String::from(" (in place)")
+                } else {
+                    format!(" (moved to {:#x})", new_ptr)
+                }
+            ),
+            Event::Dealloc { ptr, size, align } => {
+                println!(
+                    "    dealloc  {:#x}  {:>3} bytes, align {}",
+                    ptr, size, align
+                )
+            }
+        }
+    }
+}
+
+// Just the sizes, since addresses differ from run to run
+fn sizes(events: &[Event]) -> Vec<(&'static str, usize)> {
+    events
+        .iter()
+        .map(|event| match *event {
+            Event::Alloc { size, .. } => ("alloc", size),
+            Event::Realloc { new_size, .. } => ("realloc", new_size),
+            Event::Dealloc { size, .. } => ("dealloc", size),
+        })
+        .collect()
+}
+
+// ============================================================================
+// 1. The full life of a Vec0<i32>
+// ============================================================================
+
+fn _01_push_shrink_drop() {
+    let events = trace(|| {
+        let mut v: Vec0<i32> = Vec0::new(); // No allocation yet
+        for i in 0..10 {
+            v.push(i);
+        }
+        v.shrink_to_fit();
+        // v dropped here
+    });
+    print_trace(&events);
+
+    assert_eq!(
+        sizes(&events),
+        vec![
+            ("alloc", 4),    // first push: capacity 1
+            ("realloc", 8),  // 2nd push: full, double to 2
+            ("realloc", 16), // 3rd push: double to 4
+            ("realloc", 32), // 5th push: double to 8
+            ("realloc", 64), // 9th push: double to 16
+            ("realloc", 40), // shrink_to_fit: 16 slots -> 10
+            ("dealloc", 40), // drop frees the final buffer
+        ]
+    );
+    // Every call uses i32's alignment
+    assert!(events.iter().all(|event| match *event {
+        Event::Alloc { align, .. }
+        | Event::Realloc { align, .. }
+        | Event::Dealloc { align, .. } => align == 4,
+    }));
+}
+
+// ============================================================================
+// 2. Reserving up front skips the reallocs
+// ============================================================================
+
+fn _02_with_capacity() {
+    let events = trace(|| {
+        let mut v: Vec0<i32> = Vec0::with_capacity(10);
+        for i in 0..10 {
+            v.push(i);
+        }
+    });
+    print_trace(&events);
+
+    // One allocation of exactly the right size, one free
+    assert_eq!(sizes(&events), vec![("alloc", 40), ("dealloc", 40)]);
+}
+
+// ============================================================================
+// 3. Moving a Vec0 doesn't touch the allocator
+// ============================================================================
+
+fn _03_moves_are_free() {
+    let v: Vec0<i32> = (0..10).collect();
+
+    let events = trace(move || {
+        // Moving copies the (ptr, len, capacity) header, the buffer stays put
+        let moved = v;
+        let moved_again = Box::new(moved); // Only the Box allocates: 32 bytes for the header
+        *moved_again
+    });
+
+    // Only the Box's own alloc and dealloc, never the 40-byte element buffer
+    assert_eq!(events.len(), 2);
+    assert!(sizes(&events).iter().all(|(_, size)| *size != 40));
+}
+
+// ============================================================================
+// Main
+// ============================================================================
+
+fn main() {
+    run_all![
+        "Allocator internals",
+        _01_push_shrink_drop,
+        _02_with_capacity,
+        _03_moves_are_free,
+    ];
+}
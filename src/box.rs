@@ -1,14 +1,24 @@
 //! Box0 - Educational reimplementation of `Box<T>`
 
+use crate::result::Result0;
 use std::alloc::{alloc, dealloc, Layout};
+use std::cmp::Ordering;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
 use std::ptr;
 
-pub struct Box0<T> {
+/// `T` may be unsized, like `[T]`: then `ptr` is a fat pointer that
+/// carries the length next to the address.
+pub struct Box0<T: ?Sized> {
     ptr: *mut T,
 }
 
+/// The allocator couldn't provide memory for [`Box0::try_new`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllocError;
+
 impl<T> Box0<T> {
     /// Allocates memory on the heap and places `value` into it.
     /// ```
@@ -17,24 +27,65 @@ impl<T> Box0<T> {
     /// assert_eq!(*b, 42);
     /// ```
     pub fn new(value: T) -> Box0<T> {
+        // Same allocation as `try_new`, but running out of memory is fatal
+        match Self::try_new(value) {
+            Result0::Ok(b) => b,
+            Result0::Err(_) => std::alloc::handle_alloc_error(Layout::new::<T>()),
+        }
+    }
+
+    /// Like [`Box0::new`], but returns [`AllocError`] instead of aborting
+    /// when the allocator is out of memory. `value` is dropped in that case.
+    ///
+    /// `new` treats a null pointer from the allocator as fatal and calls
+    /// `handle_alloc_error`; this is the recoverable path for places that
+    /// can't afford to abort. A real allocation failure is hard to provoke
+    /// in a test: it takes a custom `#[global_allocator]` that returns null.
+    /// ```
+    /// use rustlib::r#box::{AllocError, Box0};
+    /// use rustlib::result::Result0;
+    /// let b: Result0<Box0<i32>, AllocError> = Box0::try_new(42);
+    /// assert_eq!(*b.unwrap(), 42);
+    /// ```
+    pub fn try_new(value: T) -> Result0<Box0<T>, AllocError> {
         unsafe {
             // Calculate memory layout for T
             let layout = Layout::new::<T>();
 
-            // Allocate memory
-            let ptr = alloc(layout) as *mut T;
+            // Allocate memory. Zero-sized values need none: any aligned,
+            // non-null pointer will do, and Drop knows not to free it
+            let ptr = if layout.size() == 0 {
+                ptr::NonNull::dangling().as_ptr()
+            } else {
+                alloc(layout) as *mut T
+            };
 
+            // Out of memory: hand the failure to the caller instead of aborting
             if ptr.is_null() {
-                std::alloc::handle_alloc_error(layout);
+                return Result0::Err(AllocError);
             }
 
             // Write value to allocated memory
             ptr::write(ptr, value);
 
-            Box0 { ptr }
+            Result0::Ok(Box0 { ptr })
         }
     }
 
+    /// Allocates `value` on the heap and pins it there.
+    /// The value can never be moved out again (unless `T: Unpin`),
+    /// which makes self-referential types possible.
+    /// ```
+    /// use rustlib::r#box::Box0;
+    /// let pinned = Box0::pin(42);
+    /// assert_eq!(*pinned, 42);
+    /// ```
+    pub fn pin(value: T) -> Pin<Box0<T>> {
+        // SAFETY: The value lives on the heap and Box0 never hands out
+        // a way to move it while pinned; moving the Box0 only moves the pointer.
+        unsafe { Pin::new_unchecked(Box0::new(value)) }
+    }
+
     /// Consumes the [`Box0`], returning the wrapped value.
     /// ```
     /// use rustlib::r#box::Box0;
@@ -48,7 +99,9 @@ impl<T> Box0<T> {
 
             // Deallocate
             let layout = Layout::new::<T>();
-            dealloc(self.ptr as *mut u8, layout);
+            if layout.size() != 0 {
+                dealloc(self.ptr as *mut u8, layout);
+            }
 
             // Don't run Drop (we already deallocated)
             std::mem::forget(self);
@@ -57,6 +110,19 @@ impl<T> Box0<T> {
         }
     }
 
+    /// Maps a [`Box0<T>`] to [`Box0<U>`] by applying a function to the contained value.
+    /// ```
+    /// use rustlib::r#box::Box0;
+    /// Box0::new(5).map(|x| x * 2); // Box0(10)
+    /// Box0::new("hello").map(|s| s.len()); // Box0(5)
+    /// ```
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Box0<U> {
+        let value = self.into_inner();
+        Box0::new(f(value))
+    }
+}
+
+impl<T: ?Sized> Box0<T> {
     /// Consumes and leaks the [`Box0`], returning a mutable reference with `'static` lifetime.
     /// The memory is never freed.
     /// ```
@@ -103,15 +169,25 @@ impl<T> Box0<T> {
         Box0 { ptr }
     }
 
-    /// Maps a [`Box0<T>`] to [`Box0<U>`] by applying a function to the contained value.
+    /// Hashes the heap address instead of the value, i.e. by *identity*.
+    /// Two boxes holding equal values hash differently, while the regular
+    /// [`Hash`] impl (by value) makes them collide on purpose.
+    /// Useful for pointer sets or interning, where "is it this very allocation?"
+    /// is the question, not "is it an equal value?".
     /// ```
     /// use rustlib::r#box::Box0;
-    /// Box0::new(5).map(|x| x * 2); // Box0(10)
-    /// Box0::new("hello").map(|s| s.len()); // Box0(5)
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::Hasher;
+    /// let a = Box0::new(42);
+    /// let b = Box0::new(42);
+    /// let (mut ha, mut hb) = (DefaultHasher::new(), DefaultHasher::new());
+    /// a.ptr_hash(&mut ha);
+    /// b.ptr_hash(&mut hb);
+    /// assert_ne!(ha.finish(), hb.finish()); // same value, different allocations
     /// ```
-    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Box0<U> {
-        let value = self.into_inner();
-        Box0::new(f(value))
+    pub fn ptr_hash<H: Hasher>(&self, state: &mut H) {
+        // Only the address: a slice's length isn't part of its identity
+        state.write_usize(self.ptr as *mut u8 as usize);
     }
 }
 
@@ -121,7 +197,7 @@ impl<T> Box0<T> {
 /// let b = Box0::new(42);
 /// assert_eq!(*b, 42);
 /// ```
-impl<T> Deref for Box0<T> {
+impl<T: ?Sized> Deref for Box0<T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
@@ -136,12 +212,16 @@ impl<T> Deref for Box0<T> {
 /// *b = 100;
 /// assert_eq!(*b, 100);
 /// ```
-impl<T> DerefMut for Box0<T> {
+impl<T: ?Sized> DerefMut for Box0<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         unsafe { &mut *self.ptr }
     }
 }
 
+/// Moving a [`Box0`] only moves the pointer, never the heap value,
+/// so the box itself is always [`Unpin`] (just like `Box<T>`).
+impl<T: ?Sized> Unpin for Box0<T> {}
+
 /// Dropping a [`Box0<T>`] runs the destructor for `T` and frees the heap memory.
 /// ```
 /// use rustlib::r#box::Box0;
@@ -149,15 +229,20 @@ impl<T> DerefMut for Box0<T> {
 ///     let b = Box0::new(String::from("hello"));
 /// } // b dropped here, memory freed
 /// ```
-impl<T> Drop for Box0<T> {
+impl<T: ?Sized> Drop for Box0<T> {
     fn drop(&mut self) {
         unsafe {
-            // Call destructor on the value
+            // Size and alignment of what we point to. For a slice this uses
+            // the length stored in the fat pointer, so read it before dropping
+            let layout = Layout::for_value(&*self.ptr);
+
+            // Call destructor on the value (on every element, for a slice)
             ptr::drop_in_place(self.ptr);
 
-            // Deallocate the memory
-            let layout = Layout::new::<T>();
-            dealloc(self.ptr as *mut u8, layout);
+            // Deallocate the memory. Nothing was allocated for zero-sized values
+            if layout.size() != 0 {
+                dealloc(self.ptr as *mut u8, layout);
+            }
         }
     }
 }
@@ -168,13 +253,17 @@ impl<T> Drop for Box0<T> {
 /// let b = Box0::new(42);
 /// format!("{:?}", b); // "Box0(42)"
 /// ```
-impl<T: fmt::Debug> fmt::Debug for Box0<T> {
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Box0<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_tuple("Box0").field(&**self).finish()
+        f.debug_tuple("Box0").field(&&**self).finish()
     }
 }
 
 /// Cloning creates a new [`Box0`] with a deep copy of the value.
+///
+/// `T::clone` runs on the boxed value itself (through `Deref`), so clones
+/// that touch interior state, like a `Cell` counter, see the real thing.
+/// [`Box0::new`] then places the clone in a fresh allocation.
 /// ```
 /// use rustlib::r#box::Box0;
 /// let b1 = Box0::new(42);
@@ -184,7 +273,74 @@ impl<T: fmt::Debug> fmt::Debug for Box0<T> {
 /// ```
 impl<T: Clone> Clone for Box0<T> {
     fn clone(&self) -> Self {
-        Box0::new((**self).clone())
+        Box0::new(T::clone(&**self))
+    }
+}
+
+/// Two boxes are equal when their contents are, wherever they live on the heap.
+/// ```
+/// use rustlib::r#box::Box0;
+/// assert_eq!(Box0::new(42), Box0::new(42));
+/// assert_ne!(Box0::new(1), Box0::new(2));
+/// ```
+impl<T: ?Sized + PartialEq> PartialEq for Box0<T> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+/// A box can be compared with a plain value directly, without dereferencing.
+/// ```
+/// use rustlib::r#box::Box0;
+/// assert!(Box0::new(42) == 42);
+/// ```
+impl<T: PartialEq> PartialEq<T> for Box0<T> {
+    fn eq(&self, other: &T) -> bool {
+        **self == *other
+    }
+}
+
+impl<T: ?Sized + Eq> Eq for Box0<T> {}
+
+/// Boxes are ordered by their contents, never by their addresses.
+/// ```
+/// use rustlib::r#box::Box0;
+/// assert!(Box0::new(1) < Box0::new(2));
+/// ```
+impl<T: ?Sized + PartialOrd> PartialOrd for Box0<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (**self).partial_cmp(&**other)
+    }
+}
+
+/// Total ordering by contents, so boxes can be sorted or used as
+/// `BTreeSet`/`BTreeMap` keys.
+/// ```
+/// use rustlib::r#box::Box0;
+/// use std::cmp::Ordering;
+/// assert_eq!(Box0::new("b").cmp(&Box0::new("a")), Ordering::Greater);
+/// ```
+impl<T: ?Sized + Ord> Ord for Box0<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+/// Hashing a [`Box0<T>`] hashes the contained value, not the pointer.
+/// This keeps `Hash` consistent with comparing boxes by value.
+/// ```
+/// use rustlib::r#box::Box0;
+/// use std::collections::hash_map::DefaultHasher;
+/// use std::hash::{Hash, Hasher};
+/// let mut h1 = DefaultHasher::new();
+/// let mut h2 = DefaultHasher::new();
+/// Box0::new(42).hash(&mut h1);
+/// 42.hash(&mut h2);
+/// assert_eq!(h1.finish(), h2.finish());
+/// ```
+impl<T: ?Sized + Hash> Hash for Box0<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state);
     }
 }
 
@@ -230,6 +386,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_try_new() {
+        let b = Box0::try_new(String::from("hello")).unwrap();
+        assert_eq!(*b, "hello");
+
+        // Zero-sized values never touch the allocator, so they can't fail
+        assert!(Box0::try_new(()).is_ok());
+    }
+
+    #[test]
+    fn test_pin() {
+        use std::marker::PhantomPinned;
+
+        struct SelfRef {
+            data: String,
+            ptr: *const String,
+            _pin: PhantomPinned,
+        }
+
+        let mut pinned = Box0::pin(SelfRef {
+            data: String::from("pinned"),
+            ptr: ptr::null(),
+            _pin: PhantomPinned,
+        });
+        let data_ptr = &pinned.data as *const String;
+        unsafe { pinned.as_mut().get_unchecked_mut().ptr = data_ptr };
+
+        // Moving the Pin<Box0<_>> moves the pointer, not the heap value
+        let moved = pinned;
+        assert_eq!(moved.ptr, &moved.data as *const String);
+        assert_eq!(unsafe { &*moved.ptr }, "pinned");
+    }
+
     #[test]
     fn test_leak() {
         let boxed = Box0::new(42);
@@ -261,12 +450,122 @@ mod tests {
         assert_eq!(*boxed2, 42);
     }
 
+    #[test]
+    fn test_clone_sees_original() {
+        use std::cell::Cell;
+
+        // Counts how often it has been cloned, inside the value itself
+        struct Counted {
+            clones: Cell<u32>,
+        }
+
+        impl Clone for Counted {
+            fn clone(&self) -> Self {
+                self.clones.set(self.clones.get() + 1);
+                Counted {
+                    clones: Cell::new(0),
+                }
+            }
+        }
+
+        let original = Box0::new(Counted {
+            clones: Cell::new(0),
+        });
+        let _a = original.clone();
+        let _b = original.clone();
+        assert_eq!(original.clones.get(), 2);
+    }
+
+    #[test]
+    fn test_clone_owned_resources() {
+        use std::sync::Arc;
+
+        let item = Arc::new(42);
+        let boxed1 = Box0::new(item.clone());
+        let boxed2 = boxed1.clone();
+        assert_eq!(Arc::strong_count(&item), 3);
+
+        // Each box drops exactly its own Arc
+        drop(boxed1);
+        drop(boxed2);
+        assert_eq!(Arc::strong_count(&item), 1);
+    }
+
     #[test]
     fn test_debug() {
         let boxed = Box0::new(42);
         assert_eq!(format!("{:?}", boxed), "Box0(42)");
     }
 
+    #[test]
+    fn test_hash_matches_inner_value() {
+        use std::collections::hash_map::DefaultHasher;
+
+        fn hash_of<T: Hash>(value: &T) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let boxed = Box0::new(String::from("hello"));
+        assert_eq!(hash_of(&boxed), hash_of(&String::from("hello")));
+
+        // Two separate allocations with the same value hash the same
+        let other = Box0::new(String::from("hello"));
+        assert_eq!(hash_of(&boxed), hash_of(&other));
+    }
+
+    #[test]
+    fn test_eq_and_ord() {
+        let a = Box0::new(String::from("apple"));
+        let b = Box0::new(String::from("banana"));
+
+        assert_eq!(a, Box0::new(String::from("apple")));
+        assert!(a == String::from("apple"));
+        assert_ne!(a, b);
+        assert!(a < b);
+        assert_eq!(b.cmp(&a), std::cmp::Ordering::Greater);
+    }
+
+    #[test]
+    fn test_as_collection_key() {
+        use std::collections::{BTreeSet, HashSet};
+
+        // Ordered by the strings, not by where they were allocated
+        let mut set = BTreeSet::new();
+        for word in ["pear", "apple", "fig", "apple"] {
+            set.insert(Box0::new(String::from(word)));
+        }
+        let words: Vec<&str> = set.iter().map(|b| b.as_str()).collect();
+        assert_eq!(words, vec!["apple", "fig", "pear"]);
+
+        // Hash and Eq agree, so an equal box in a new allocation is found
+        let mut hashed = HashSet::new();
+        hashed.insert(Box0::new(7));
+        assert!(hashed.contains(&Box0::new(7)));
+        assert!(!hashed.contains(&Box0::new(8)));
+    }
+
+    #[test]
+    fn test_ptr_hash_uses_identity() {
+        use std::collections::hash_map::DefaultHasher;
+
+        fn ptr_hash_of<T>(value: &Box0<T>) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.ptr_hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let boxed = Box0::new(String::from("hello"));
+        let other = Box0::new(String::from("hello"));
+        assert_ne!(ptr_hash_of(&boxed), ptr_hash_of(&other));
+
+        // Moving the box moves the pointer, not the allocation
+        let before = ptr_hash_of(&boxed);
+        let moved = boxed;
+        assert_eq!(ptr_hash_of(&moved), before);
+    }
+
     #[test]
     fn test_with_string() {
         let boxed = Box0::new(String::from("hello"));
@@ -302,6 +601,14 @@ mod tests {
         assert_eq!(take_str(&boxed), 5);
     }
 
+    #[test]
+    fn test_zero_sized() {
+        let unit = Box0::new(());
+        assert_eq!(*unit, ());
+        assert_eq!(unit.into_inner(), ());
+        drop(Box0::new(()));
+    }
+
     #[test]
     fn test_nested_box() {
         let boxed = Box0::new(Box0::new(42));
@@ -0,0 +1,812 @@
+//! BTreeMap0 - Educational reimplementation of `BTreeMap<K, V>`
+//!
+//! A B-tree keeps many keys per node, sorted, with one more child than keys.
+//! Every leaf sits at the same depth, so lookups are O(log n) and, unlike a
+//! binary tree, each node fills a few cache lines instead of one key per allocation.
+//!
+//! ```text
+//!                    [ 20 | 40 ]
+//!                   /     |     \
+//!        [ 5 | 10 ]   [ 25 ]   [ 50 | 60 | 70 ]
+//! ```
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Bound, RangeBounds};
+
+/// Minimum degree: every node except the root holds between
+/// `B - 1` and `2 * B - 1` keys. With `B = 2` each node has at most 4 children
+/// (a B-tree of order 4), small enough that tests hit splits and merges often.
+const B: usize = 2;
+const MAX_KEYS: usize = 2 * B - 1;
+
+struct Node<K, V> {
+    keys: Vec<K>,
+    vals: Vec<V>,
+    // Empty for leaves, otherwise always `keys.len() + 1` children
+    children: Vec<Node<K, V>>,
+}
+
+impl<K, V> Node<K, V> {
+    fn new() -> Node<K, V> {
+        Node {
+            keys: Vec::new(),
+            vals: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    fn is_full(&self) -> bool {
+        self.keys.len() == MAX_KEYS
+    }
+}
+
+pub struct BTreeMap0<K, V> {
+    root: Option<Node<K, V>>,
+    len: usize,
+}
+
+impl<K: Ord, V> BTreeMap0<K, V> {
+    /// Creates an empty map without allocating.
+    /// ```
+    /// use rustlib::btree_map::BTreeMap0;
+    /// let map: BTreeMap0<i32, &str> = BTreeMap0::new();
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn new() -> BTreeMap0<K, V> {
+        BTreeMap0 { root: None, len: 0 }
+    }
+
+    /// Returns the number of entries in the map.
+    /// ```
+    /// use rustlib::btree_map::BTreeMap0;
+    /// let mut map = BTreeMap0::new();
+    /// map.insert(1, "a");
+    /// assert_eq!(map.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map contains no entries.
+    /// ```
+    /// use rustlib::btree_map::BTreeMap0;
+    /// let map: BTreeMap0<i32, i32> = BTreeMap0::new();
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a reference to the value for `key`, or [`None`] if absent.
+    /// ```
+    /// use rustlib::btree_map::BTreeMap0;
+    /// let mut map = BTreeMap0::new();
+    /// map.insert(1, "a");
+    /// assert_eq!(map.get(&1), Some(&"a"));
+    /// assert_eq!(map.get(&2), None);
+    /// ```
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let mut node = self.root.as_ref()?;
+        loop {
+            match node.keys.binary_search(key) {
+                Ok(i) => return Some(&node.vals[i]),
+                Err(_) if node.is_leaf() => return None,
+                Err(i) => node = &node.children[i],
+            }
+        }
+    }
+
+    /// Returns a mutable reference to the value for `key`, or [`None`] if absent.
+    /// ```
+    /// use rustlib::btree_map::BTreeMap0;
+    /// let mut map = BTreeMap0::new();
+    /// map.insert(1, 10);
+    /// *map.get_mut(&1).unwrap() += 5;
+    /// assert_eq!(map.get(&1), Some(&15));
+    /// ```
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let mut node = self.root.as_mut()?;
+        loop {
+            match node.keys.binary_search(key) {
+                Ok(i) => return Some(&mut node.vals[i]),
+                Err(_) if node.is_leaf() => return None,
+                Err(i) => node = &mut node.children[i],
+            }
+        }
+    }
+
+    /// Returns `true` if the map contains `key`.
+    /// ```
+    /// use rustlib::btree_map::BTreeMap0;
+    /// let mut map = BTreeMap0::new();
+    /// map.insert(1, "a");
+    /// assert!(map.contains_key(&1));
+    /// assert!(!map.contains_key(&2));
+    /// ```
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Inserts a key-value pair, returning the old value if the key was present.
+    ///
+    /// Full nodes are split on the way down, so there is always room
+    /// in the leaf we finally insert into. When the root is full, it is split
+    /// and the tree grows one level taller - the only way a B-tree gains height.
+    /// ```
+    /// use rustlib::btree_map::BTreeMap0;
+    /// let mut map = BTreeMap0::new();
+    /// assert_eq!(map.insert(1, "a"), None);
+    /// assert_eq!(map.insert(1, "b"), Some("a"));
+    /// assert_eq!(map.get(&1), Some(&"b"));
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let mut root = self.root.take().unwrap_or_else(|| Node::new());
+
+        if root.is_full() {
+            let mut new_root = Node::new();
+            new_root.children.push(root);
+            split_child(&mut new_root, 0);
+            root = new_root;
+        }
+
+        let old = insert_non_full(&mut root, key, value);
+        self.root = Some(root);
+
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    /// Removes `key` from the map, returning its value if it was present.
+    ///
+    /// Before descending into a child we make sure it has at least `B` keys
+    /// (borrowing from a sibling or merging with one), so removing from it
+    /// can never leave a node under-full.
+    /// ```
+    /// use rustlib::btree_map::BTreeMap0;
+    /// let mut map = BTreeMap0::new();
+    /// map.insert(1, "a");
+    /// assert_eq!(map.remove(&1), Some("a"));
+    /// assert_eq!(map.remove(&1), None);
+    /// ```
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let root = self.root.as_mut()?;
+        let removed = remove_from(root, key);
+
+        // A root left without keys shrinks the tree by one level
+        if root.keys.is_empty() {
+            self.root = if root.is_leaf() {
+                None
+            } else {
+                root.children.pop()
+            };
+        }
+
+        if removed.is_some() {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    /// Returns an iterator over the entries, sorted by key.
+    /// ```
+    /// use rustlib::btree_map::BTreeMap0;
+    /// let mut map = BTreeMap0::new();
+    /// map.insert(3, "c");
+    /// map.insert(1, "a");
+    /// map.insert(2, "b");
+    /// let keys: Vec<_> = map.iter().map(|(k, _)| *k).collect();
+    /// assert_eq!(keys, vec![1, 2, 3]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let mut cursor = Cursor { stack: Vec::new() };
+        if let Some(root) = self.root.as_ref() {
+            cursor.push_leftmost(root);
+        }
+        Iter {
+            cursor,
+            remaining: self.len,
+        }
+    }
+
+    /// Returns an iterator over the entries whose keys fall in `range`, sorted by key.
+    ///
+    /// Finding the start is a single O(log n) descent; after that
+    /// the iterator walks forward until a key passes the end bound.
+    /// ```
+    /// use rustlib::btree_map::BTreeMap0;
+    /// let mut map = BTreeMap0::new();
+    /// for i in 0..10 {
+    ///     map.insert(i, i * 10);
+    /// }
+    /// let keys: Vec<_> = map.range(3..6).map(|(k, _)| *k).collect();
+    /// assert_eq!(keys, vec![3, 4, 5]);
+    /// ```
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> Range<'_, K, V>
+    where
+        K: Clone,
+    {
+        let mut cursor = Cursor { stack: Vec::new() };
+        let mut node = self.root.as_ref();
+
+        while let Some(current) = node {
+            // Index of the first key that is inside the start bound
+            let i = match range.start_bound() {
+                Bound::Included(start) => current.keys.partition_point(|k| k < start),
+                Bound::Excluded(start) => current.keys.partition_point(|k| k <= start),
+                Bound::Unbounded => 0,
+            };
+            cursor.stack.push((current, i));
+            node = current.children.get(i);
+        }
+
+        Range {
+            cursor,
+            end: range.end_bound().cloned(),
+        }
+    }
+}
+
+/// Splits the full child at `parent.children[i]` in two,
+/// moving its median key up into `parent`.
+///
+/// ```text
+///   parent: [ 10 | 50 ]            parent: [ 10 | 30 | 50 ]
+///                |          =>                 /    \
+///   child:  [ 20 | 30 | 40 ]          [ 20 ]  [ 40 ]
+/// ```
+fn split_child<K, V>(parent: &mut Node<K, V>, i: usize) {
+    let child = &mut parent.children[i];
+
+    let mut right = Node::new();
+    right.keys = child.keys.split_off(B);
+    right.vals = child.vals.split_off(B);
+    if !child.is_leaf() {
+        right.children = child.children.split_off(B);
+    }
+
+    let median_key = child.keys.pop().unwrap();
+    let median_val = child.vals.pop().unwrap();
+
+    parent.keys.insert(i, median_key);
+    parent.vals.insert(i, median_val);
+    parent.children.insert(i + 1, right);
+}
+
+fn insert_non_full<K: Ord, V>(node: &mut Node<K, V>, key: K, value: V) -> Option<V> {
+    let mut i = match node.keys.binary_search(&key) {
+        Ok(i) => return Some(std::mem::replace(&mut node.vals[i], value)),
+        Err(i) => i,
+    };
+
+    if node.is_leaf() {
+        node.keys.insert(i, key);
+        node.vals.insert(i, value);
+        return None;
+    }
+
+    if node.children[i].is_full() {
+        split_child(node, i);
+        // The median moved up to keys[i]: pick the side our key belongs to
+        match key.cmp(&node.keys[i]) {
+            Ordering::Equal => return Some(std::mem::replace(&mut node.vals[i], value)),
+            Ordering::Greater => i += 1,
+            Ordering::Less => {}
+        }
+    }
+
+    insert_non_full(&mut node.children[i], key, value)
+}
+
+fn remove_from<K: Ord, V>(node: &mut Node<K, V>, key: &K) -> Option<V> {
+    match node.keys.binary_search(key) {
+        Ok(i) if node.is_leaf() => {
+            node.keys.remove(i);
+            Some(node.vals.remove(i))
+        }
+        Ok(i) => {
+            if node.children[i].keys.len() >= B {
+                // Replace with the predecessor (largest key in the left subtree)
+                let (k, v) = remove_max(&mut node.children[i]);
+                node.keys[i] = k;
+                Some(std::mem::replace(&mut node.vals[i], v))
+            } else if node.children[i + 1].keys.len() >= B {
+                // Replace with the successor (smallest key in the right subtree)
+                let (k, v) = remove_min(&mut node.children[i + 1]);
+                node.keys[i] = k;
+                Some(std::mem::replace(&mut node.vals[i], v))
+            } else {
+                // Both neighbours are minimal: merge them around the key, then recurse
+                merge_children(node, i);
+                remove_from(&mut node.children[i], key)
+            }
+        }
+        Err(_) if node.is_leaf() => None,
+        Err(i) => {
+            let i = ensure_child_has_spare_key(node, i);
+            remove_from(&mut node.children[i], key)
+        }
+    }
+}
+
+fn remove_max<K, V>(node: &mut Node<K, V>) -> (K, V) {
+    if node.is_leaf() {
+        return (node.keys.pop().unwrap(), node.vals.pop().unwrap());
+    }
+    let last = ensure_child_has_spare_key(node, node.children.len() - 1);
+    remove_max(&mut node.children[last])
+}
+
+fn remove_min<K, V>(node: &mut Node<K, V>) -> (K, V) {
+    if node.is_leaf() {
+        return (node.keys.remove(0), node.vals.remove(0));
+    }
+    let first = ensure_child_has_spare_key(node, 0);
+    remove_min(&mut node.children[first])
+}
+
+/// Makes sure `node.children[i]` has at least `B` keys so one can be removed
+/// from it. Returns the child's index afterwards (merging with the left
+/// sibling shifts it to `i - 1`).
+fn ensure_child_has_spare_key<K, V>(node: &mut Node<K, V>, i: usize) -> usize {
+    if node.children[i].keys.len() >= B {
+        return i;
+    }
+
+    let has_left = i > 0;
+    let has_right = i + 1 < node.children.len();
+
+    if has_left && node.children[i - 1].keys.len() >= B {
+        rotate_right(node, i - 1);
+        i
+    } else if has_right && node.children[i + 1].keys.len() >= B {
+        rotate_left(node, i);
+        i
+    } else if has_right {
+        merge_children(node, i);
+        i
+    } else {
+        merge_children(node, i - 1);
+        i - 1
+    }
+}
+
+/// Moves the separator `keys[i]` down into `children[i + 1]`
+/// and the last key of `children[i]` up to replace it.
+fn rotate_right<K, V>(node: &mut Node<K, V>, i: usize) {
+    let (left, right) = node.children.split_at_mut(i + 1);
+    let donor = &mut left[i];
+    let child = &mut right[0];
+
+    let k = std::mem::replace(&mut node.keys[i], donor.keys.pop().unwrap());
+    let v = std::mem::replace(&mut node.vals[i], donor.vals.pop().unwrap());
+    child.keys.insert(0, k);
+    child.vals.insert(0, v);
+    if let Some(grandchild) = donor.children.pop() {
+        child.children.insert(0, grandchild);
+    }
+}
+
+/// Moves the separator `keys[i]` down into `children[i]`
+/// and the first key of `children[i + 1]` up to replace it.
+fn rotate_left<K, V>(node: &mut Node<K, V>, i: usize) {
+    let (left, right) = node.children.split_at_mut(i + 1);
+    let child = &mut left[i];
+    let donor = &mut right[0];
+
+    let k = std::mem::replace(&mut node.keys[i], donor.keys.remove(0));
+    let v = std::mem::replace(&mut node.vals[i], donor.vals.remove(0));
+    child.keys.push(k);
+    child.vals.push(v);
+    if !donor.is_leaf() {
+        child.children.push(donor.children.remove(0));
+    }
+}
+
+/// Merges `children[i + 1]` and the separator `keys[i]` into `children[i]`.
+///
+/// ```text
+///   node:  [ 10 | 30 | 50 ]           node: [ 10 | 50 ]
+///                /    \        =>                |
+///           [ 20 ]  [ 40 ]             [ 20 | 30 | 40 ]
+/// ```
+fn merge_children<K, V>(node: &mut Node<K, V>, i: usize) {
+    let right = node.children.remove(i + 1);
+    let k = node.keys.remove(i);
+    let v = node.vals.remove(i);
+
+    let left = &mut node.children[i];
+    left.keys.push(k);
+    left.vals.push(v);
+    left.keys.extend(right.keys);
+    left.vals.extend(right.vals);
+    left.children.extend(right.children);
+}
+
+impl<K: Ord, V> Default for BTreeMap0<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Debug formatting shows the entries in key order.
+/// ```
+/// use rustlib::btree_map::BTreeMap0;
+/// let mut map = BTreeMap0::new();
+/// map.insert(2, "b");
+/// map.insert(1, "a");
+/// assert_eq!(format!("{:?}", map), r#"{1: "a", 2: "b"}"#);
+/// ```
+impl<K: Ord + fmt::Debug, V: fmt::Debug> fmt::Debug for BTreeMap0<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+// ============================================================================
+// Iterators
+// ============================================================================
+
+/// In-order traversal state shared by [`Iter`] and [`Range`].
+///
+/// Each stack entry `(node, i)` means "the next key to yield from `node`
+/// is `keys[i]`, and everything left of it has been visited".
+struct Cursor<'a, K, V> {
+    stack: Vec<(&'a Node<K, V>, usize)>,
+}
+
+// Not derived: that would require `K: Clone, V: Clone`, but only references are copied
+impl<K, V> Clone for Cursor<'_, K, V> {
+    fn clone(&self) -> Self {
+        Cursor {
+            stack: self.stack.clone(),
+        }
+    }
+}
+
+impl<'a, K, V> Cursor<'a, K, V> {
+    fn push_leftmost(&mut self, mut node: &'a Node<K, V>) {
+        loop {
+            self.stack.push((node, 0));
+            match node.children.first() {
+                Some(child) => node = child,
+                None => break,
+            }
+        }
+    }
+
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        loop {
+            let (node, i) = self.stack.last_mut()?;
+            let node: &'a Node<K, V> = node;
+
+            if *i < node.keys.len() {
+                let index = *i;
+                *i += 1;
+                // Keys right of this one live in the next child's subtree
+                if let Some(child) = node.children.get(index + 1) {
+                    self.push_leftmost(child);
+                }
+                return Some((&node.keys[index], &node.vals[index]));
+            }
+
+            self.stack.pop();
+        }
+    }
+}
+
+/// Iterator over the entries of a [`BTreeMap0`] in key order.
+/// Created by calling [`BTreeMap0::iter`].
+pub struct Iter<'a, K, V> {
+    cursor: Cursor<'a, K, V>,
+    remaining: usize,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.cursor.next()?;
+        self.remaining -= 1;
+        Some(entry)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+/// Debug formatting shows the entries not yet yielded.
+/// ```
+/// use rustlib::btree_map::BTreeMap0;
+/// let mut map = BTreeMap0::new();
+/// map.insert(1, "a");
+/// map.insert(2, "b");
+/// let mut iter = map.iter();
+/// iter.next();
+/// assert_eq!(format!("{:?}", iter), r#"Iter([(2, "b")])"#);
+/// ```
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Iter<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Walk a copy of the cursor so formatting doesn't advance the iterator
+        let mut cursor = self.cursor.clone();
+        let remaining: Vec<_> = std::iter::from_fn(|| cursor.next()).collect();
+        f.debug_tuple("Iter").field(&remaining).finish()
+    }
+}
+
+/// Iterator over a sub-range of a [`BTreeMap0`] in key order.
+/// Created by calling [`BTreeMap0::range`].
+pub struct Range<'a, K, V> {
+    cursor: Cursor<'a, K, V>,
+    end: Bound<K>,
+}
+
+impl<K: Ord, V> Range<'_, K, V> {
+    fn before_end(&self, k: &K) -> bool {
+        match &self.end {
+            Bound::Included(end) => k <= end,
+            Bound::Excluded(end) => k < end,
+            Bound::Unbounded => true,
+        }
+    }
+}
+
+impl<'a, K: Ord, V> Iterator for Range<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (k, v) = self.cursor.next()?;
+        if self.before_end(k) {
+            Some((k, v))
+        } else {
+            // Keys only grow from here: stop for good
+            self.cursor.stack.clear();
+            None
+        }
+    }
+}
+
+/// Debug formatting shows the entries of the range not yet yielded.
+/// ```
+/// use rustlib::btree_map::BTreeMap0;
+/// let mut map = BTreeMap0::new();
+/// for i in 0..5 {
+///     map.insert(i, i * 10);
+/// }
+/// let mut range = map.range(1..3);
+/// range.next();
+/// assert_eq!(format!("{:?}", range), "Range([(2, 20)])");
+/// ```
+impl<K: Ord + fmt::Debug, V: fmt::Debug> fmt::Debug for Range<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut cursor = self.cursor.clone();
+        let remaining: Vec<_> = std::iter::from_fn(|| cursor.next())
+            .take_while(|(k, _)| self.before_end(k))
+            .collect();
+        f.debug_tuple("Range").field(&remaining).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    /// Deterministic pseudo-random keys so failures are reproducible
+    fn shuffled_keys(n: u64) -> Vec<u64> {
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        (0..n)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state % (n * 2)
+            })
+            .collect()
+    }
+
+    /// Walks the tree checking the B-tree invariants
+    fn check_invariants<K: Ord, V>(map: &BTreeMap0<K, V>) {
+        fn check<K: Ord, V>(node: &Node<K, V>, is_root: bool) -> usize {
+            assert!(node.keys.len() <= MAX_KEYS);
+            if !is_root {
+                assert!(node.keys.len() >= B - 1);
+            }
+            assert_eq!(node.keys.len(), node.vals.len());
+            assert!(node.keys.windows(2).all(|w| w[0] < w[1]));
+
+            if node.is_leaf() {
+                return 1;
+            }
+            assert_eq!(node.children.len(), node.keys.len() + 1);
+            let depths: Vec<usize> = node.children.iter().map(|c| check(c, false)).collect();
+            assert!(
+                depths.iter().all(|&d| d == depths[0]),
+                "leaves at different depths"
+            );
+            depths[0] + 1
+        }
+
+        if let Some(root) = map.root.as_ref() {
+            check(root, true);
+        }
+    }
+
+    #[test]
+    fn test_new() {
+        let map: BTreeMap0<i32, i32> = BTreeMap0::new();
+        assert_eq!(map.len(), 0);
+        assert!(map.is_empty());
+        assert_eq!(map.get(&1), None);
+        assert_eq!(map.iter().next(), None);
+    }
+
+    #[test]
+    fn test_insert_and_get() {
+        let mut map = BTreeMap0::new();
+        assert_eq!(map.insert(5, "five"), None);
+        assert_eq!(map.insert(1, "one"), None);
+        assert_eq!(map.insert(5, "FIVE"), Some("five"));
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&5), Some(&"FIVE"));
+        assert_eq!(map.get(&1), Some(&"one"));
+        assert_eq!(map.get(&3), None);
+        assert!(map.contains_key(&1));
+        assert!(!map.contains_key(&3));
+    }
+
+    #[test]
+    fn test_get_mut() {
+        let mut map = BTreeMap0::new();
+        for i in 0..20 {
+            map.insert(i, i);
+        }
+        for i in 0..20 {
+            *map.get_mut(&i).unwrap() *= 10;
+        }
+        assert_eq!(map.get(&7), Some(&70));
+        assert_eq!(map.get_mut(&20), None);
+    }
+
+    #[test]
+    fn test_splits_keep_invariants() {
+        let mut map = BTreeMap0::new();
+        for i in 0..200 {
+            map.insert(i, i);
+            check_invariants(&map);
+        }
+        for i in (200..400).rev() {
+            map.insert(i, i);
+            check_invariants(&map);
+        }
+        assert_eq!(map.len(), 400);
+    }
+
+    #[test]
+    fn test_iter_matches_std() {
+        let mut map = BTreeMap0::new();
+        let mut expected = BTreeMap::new();
+        for key in shuffled_keys(500) {
+            assert_eq!(map.insert(key, key * 3), expected.insert(key, key * 3));
+        }
+
+        assert_eq!(map.len(), expected.len());
+        assert_eq!(
+            map.iter().size_hint(),
+            (expected.len(), Some(expected.len()))
+        );
+        assert!(map.iter().eq(expected.iter()));
+    }
+
+    #[test]
+    fn test_remove_matches_std() {
+        let mut map = BTreeMap0::new();
+        let mut expected = BTreeMap::new();
+        let keys = shuffled_keys(500);
+        for &key in &keys {
+            map.insert(key, key.to_string());
+            expected.insert(key, key.to_string());
+        }
+
+        // Remove every other key (including some that aren't present)
+        for key in (0..1000).step_by(2) {
+            assert_eq!(map.remove(&key), expected.remove(&key));
+            check_invariants(&map);
+        }
+        assert_eq!(map.len(), expected.len());
+        assert!(map.iter().eq(expected.iter()));
+
+        // Then everything else, in insertion order
+        for key in keys {
+            assert_eq!(map.remove(&key), expected.remove(&key));
+            check_invariants(&map);
+        }
+        assert!(map.is_empty());
+        assert!(map.root.is_none());
+    }
+
+    #[test]
+    fn test_remove_drops_values() {
+        use std::sync::Arc;
+
+        let item = Arc::new(());
+        let mut map = BTreeMap0::new();
+        for i in 0..50 {
+            map.insert(i, item.clone());
+        }
+        assert_eq!(Arc::strong_count(&item), 51);
+
+        for i in 0..25 {
+            drop(map.remove(&i));
+        }
+        assert_eq!(Arc::strong_count(&item), 26);
+
+        drop(map);
+        assert_eq!(Arc::strong_count(&item), 1);
+    }
+
+    #[test]
+    fn test_range_matches_std() {
+        let mut map = BTreeMap0::new();
+        let mut expected = BTreeMap::new();
+        for key in shuffled_keys(300) {
+            map.insert(key, ());
+            expected.insert(key, ());
+        }
+
+        for (lo, hi) in [(0, 600), (10, 20), (100, 101), (250, 250), (590, 700)] {
+            assert!(map.range(lo..hi).eq(expected.range(lo..hi)));
+            assert!(map.range(lo..=hi).eq(expected.range(lo..=hi)));
+            assert!(map.range(lo..).eq(expected.range(lo..)));
+            assert!(map.range(..hi).eq(expected.range(..hi)));
+        }
+        assert!(map.range(..).eq(expected.range(..)));
+
+        let excluded = (Bound::Excluded(10), Bound::Excluded(50));
+        assert!(map.range(excluded).eq(expected.range(excluded)));
+    }
+
+    #[test]
+    fn test_range_empty_map() {
+        let map: BTreeMap0<i32, i32> = BTreeMap0::new();
+        assert_eq!(map.range(0..10).next(), None);
+    }
+
+    #[test]
+    fn test_debug() {
+        let mut map = BTreeMap0::new();
+        map.insert(2, "b");
+        map.insert(1, "a");
+        assert_eq!(format!("{:?}", map), r#"{1: "a", 2: "b"}"#);
+    }
+
+    #[test]
+    fn test_iterator_debug_shows_remaining() {
+        let mut map = BTreeMap0::new();
+        for i in 0..10 {
+            map.insert(i, i);
+        }
+
+        let mut iter = map.iter();
+        iter.nth(7);
+        assert_eq!(format!("{:?}", iter), "Iter([(8, 8), (9, 9)])");
+        // Formatting didn't consume anything
+        assert_eq!(iter.next(), Some((&8, &8)));
+
+        let mut range = map.range(3..=5);
+        range.next();
+        assert_eq!(format!("{:?}", range), "Range([(4, 4), (5, 5)])");
+        assert_eq!(range.count(), 2);
+
+        let empty: BTreeMap0<i32, i32> = BTreeMap0::new();
+        assert_eq!(format!("{:?}", empty.iter()), "Iter([])");
+    }
+}
@@ -145,6 +145,87 @@ impl<T: Copy> Cell0<T> {
         let old = self.get();
         self.set(f(old));
     }
+
+    /// Adds `rhs` to the contained value, the `cell += rhs` of a [`Cell0`].
+    /// Shorthand for `cell.update(|x| x + rhs)`, handy for counters.
+    /// ```
+    /// use rustlib::cell::Cell0;
+    /// let hits = Cell0::new(0usize);
+    /// hits.add_assign(1);
+    /// hits.add_assign(1);
+    /// assert_eq!(hits.get(), 2);
+    /// ```
+    pub fn add_assign(&self, rhs: T)
+    where
+        T: std::ops::Add<Output = T>,
+    {
+        self.update(|x| x + rhs);
+    }
+
+    /// Subtracts `rhs` from the contained value.
+    /// ```
+    /// use rustlib::cell::Cell0;
+    /// let cell = Cell0::new(10);
+    /// cell.sub_assign(3);
+    /// assert_eq!(cell.get(), 7);
+    /// ```
+    pub fn sub_assign(&self, rhs: T)
+    where
+        T: std::ops::Sub<Output = T>,
+    {
+        self.update(|x| x - rhs);
+    }
+
+    /// Multiplies the contained value by `rhs`.
+    /// ```
+    /// use rustlib::cell::Cell0;
+    /// let cell = Cell0::new(4);
+    /// cell.mul_assign(5);
+    /// assert_eq!(cell.get(), 20);
+    /// ```
+    pub fn mul_assign(&self, rhs: T)
+    where
+        T: std::ops::Mul<Output = T>,
+    {
+        self.update(|x| x * rhs);
+    }
+
+    /// Divides the contained value by `rhs`.
+    /// ```
+    /// use rustlib::cell::Cell0;
+    /// let cell = Cell0::new(20);
+    /// cell.div_assign(4);
+    /// assert_eq!(cell.get(), 5);
+    /// ```
+    pub fn div_assign(&self, rhs: T)
+    where
+        T: std::ops::Div<Output = T>,
+    {
+        self.update(|x| x / rhs);
+    }
+}
+
+impl<T: Copy + PartialEq> Cell0<T> {
+    /// Replaces the value with `new` only if it currently equals `current`.
+    /// Returns the previous value either way, so the swap happened if the
+    /// returned value equals `current`.
+    ///
+    /// This mirrors `AtomicUsize::compare_exchange`, but since [`Cell0`] is
+    /// single-threaded, nothing can change the value between the read and the write.
+    /// ```
+    /// use rustlib::cell::Cell0;
+    /// let cell = Cell0::new(10);
+    /// assert_eq!(cell.compare_and_swap(10, 20), 10); // matched, swapped
+    /// assert_eq!(cell.compare_and_swap(10, 30), 20); // no match, unchanged
+    /// assert_eq!(cell.get(), 20);
+    /// ```
+    pub fn compare_and_swap(&self, current: T, new: T) -> T {
+        let old = self.get();
+        if old == current {
+            self.set(new);
+        }
+        old
+    }
 }
 
 impl<T: Default> Cell0<T> {
@@ -255,6 +336,35 @@ mod tests {
         assert_eq!(cell.get(), 20);
     }
 
+    #[test]
+    fn test_arithmetic_assign() {
+        let counter = Cell0::new(0usize);
+        // Only a shared reference needed, just like update
+        let shared = &counter;
+        shared.add_assign(10);
+        shared.sub_assign(4);
+        shared.mul_assign(3);
+        shared.div_assign(2);
+        assert_eq!(counter.get(), 9);
+
+        let cell = Cell0::new(1.5f64);
+        cell.mul_assign(2.0);
+        assert_eq!(cell.get(), 3.0);
+    }
+
+    #[test]
+    fn test_compare_and_swap() {
+        let cell = Cell0::new(10);
+
+        // Matching value: swapped, old value returned
+        assert_eq!(cell.compare_and_swap(10, 20), 10);
+        assert_eq!(cell.get(), 20);
+
+        // Non-matching value: untouched, current value returned
+        assert_eq!(cell.compare_and_swap(10, 30), 20);
+        assert_eq!(cell.get(), 20);
+    }
+
     #[test]
     fn test_clone() {
         let cell = Cell0::new(42);
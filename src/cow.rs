@@ -0,0 +1,202 @@
+//! Cow0 - Educational reimplementation of `Cow<'a, T>` (clone-on-write)
+
+use std::ops::Deref;
+
+/// Either a borrowed `&'a T` or an owned `T`.
+///
+/// Reading works the same for both, through [`Deref`]. A clone is only made
+/// the first time someone needs to write: [`to_mut`](Cow0::to_mut) turns a
+/// `Borrowed` into an `Owned`, then hands out `&mut` to that copy.
+/// Code that never writes never pays for the clone.
+///
+/// Unlike std's `Cow`, the owned form is `T` itself rather than `T::Owned`,
+/// so this works for `Clone` types but not for `str` or `[T]`.
+pub enum Cow0<'a, T: 'a + Clone> {
+    Borrowed(&'a T),
+    Owned(T),
+}
+
+pub use Cow0::{Borrowed, Owned};
+
+impl<'a, T: Clone> Cow0<'a, T> {
+    /// Returns `true` if the data is borrowed.
+    /// ```
+    /// use rustlib::cow::{Cow0, Borrowed, Owned};
+    /// assert!(Borrowed(&1).is_borrowed());
+    /// assert!(!Owned::<i32>(1).is_borrowed());
+    /// ```
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self, Borrowed(_))
+    }
+
+    /// Returns `true` if the data is owned.
+    /// ```
+    /// use rustlib::cow::{Cow0, Borrowed, Owned};
+    /// assert!(Owned::<i32>(1).is_owned());
+    /// assert!(!Borrowed(&1).is_owned());
+    /// ```
+    pub fn is_owned(&self) -> bool {
+        !self.is_borrowed()
+    }
+
+    /// Extracts the owned data, cloning it if it was borrowed.
+    /// ```
+    /// use rustlib::cow::{Cow0, Borrowed, Owned};
+    /// let s = String::from("hi");
+    /// assert_eq!(Borrowed(&s).into_owned(), "hi"); // cloned
+    /// assert_eq!(Owned(s).into_owned(), "hi"); // moved out, no clone
+    /// ```
+    pub fn into_owned(self) -> T {
+        match self {
+            Borrowed(borrowed) => borrowed.clone(),
+            Owned(owned) => owned,
+        }
+    }
+
+    /// Returns a mutable reference to owned data, cloning first if borrowed.
+    /// Only the first call on a `Borrowed` clones: afterwards it's `Owned`.
+    /// ```
+    /// use rustlib::cow::{Cow0, Borrowed};
+    /// let original = vec![1, 2];
+    /// let mut cow = Borrowed(&original);
+    /// cow.to_mut().push(3);
+    /// assert!(cow.is_owned());
+    /// assert_eq!(*cow, vec![1, 2, 3]);
+    /// assert_eq!(original, vec![1, 2]); // untouched
+    /// ```
+    pub fn to_mut(&mut self) -> &mut T {
+        if let Borrowed(borrowed) = *self {
+            *self = Owned(borrowed.clone());
+        }
+        match self {
+            Owned(owned) => owned,
+            Borrowed(_) => unreachable!(),
+        }
+    }
+}
+
+/// Dereferencing a [`Cow0<T>`] yields `&T`, whichever variant it is.
+/// ```
+/// use rustlib::cow::{Cow0, Borrowed, Owned};
+/// let s = String::from("hello");
+/// let a: Cow0<String> = Borrowed(&s);
+/// let b: Cow0<String> = Owned(String::from("hello"));
+/// assert_eq!(a.len(), b.len());
+/// ```
+impl<T: Clone> Deref for Cow0<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        match self {
+            Borrowed(borrowed) => borrowed,
+            Owned(owned) => owned,
+        }
+    }
+}
+
+/// Cloning a `Borrowed` copies the reference; cloning an `Owned` clones the value.
+/// ```
+/// use rustlib::cow::{Cow0, Borrowed, Owned};
+/// let s = String::from("hi");
+/// assert!(Borrowed(&s).clone().is_borrowed());
+/// assert!(Owned(s).clone().is_owned());
+/// ```
+impl<T: Clone> Clone for Cow0<'_, T> {
+    fn clone(&self) -> Self {
+        match self {
+            Borrowed(borrowed) => Borrowed(borrowed),
+            Owned(owned) => Owned(owned.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    // Counts clones so the tests can see when a copy actually happens
+    struct Counted<'c> {
+        value: i32,
+        clones: &'c Cell<usize>,
+    }
+
+    impl Clone for Counted<'_> {
+        fn clone(&self) -> Self {
+            self.clones.set(self.clones.get() + 1);
+            Counted {
+                value: self.value,
+                clones: self.clones,
+            }
+        }
+    }
+
+    #[test]
+    fn test_deref() {
+        let s = String::from("hello");
+        let borrowed: Cow0<String> = Borrowed(&s);
+        let owned: Cow0<String> = Owned(String::from("world"));
+        assert_eq!(*borrowed, "hello");
+        assert_eq!(owned.len(), 5);
+    }
+
+    #[test]
+    fn test_to_mut_clones_once() {
+        let clones = Cell::new(0);
+        let original = Counted {
+            value: 1,
+            clones: &clones,
+        };
+        let mut cow = Borrowed(&original);
+
+        cow.to_mut().value += 1;
+        cow.to_mut().value += 1;
+        assert_eq!(clones.get(), 1);
+        assert_eq!(cow.value, 3);
+        assert_eq!(original.value, 1);
+    }
+
+    #[test]
+    fn test_read_only_never_clones() {
+        let clones = Cell::new(0);
+        let original = Counted {
+            value: 7,
+            clones: &clones,
+        };
+        let cow = Borrowed(&original);
+        assert_eq!(cow.value, 7);
+        assert_eq!(clones.get(), 0);
+    }
+
+    #[test]
+    fn test_into_owned() {
+        let clones = Cell::new(0);
+        let original = Counted {
+            value: 5,
+            clones: &clones,
+        };
+
+        assert_eq!(Borrowed(&original).into_owned().value, 5);
+        assert_eq!(clones.get(), 1);
+
+        assert_eq!(Owned(original).into_owned().value, 5);
+        assert_eq!(clones.get(), 1); // moved, not cloned
+    }
+
+    #[test]
+    fn test_clone() {
+        let clones = Cell::new(0);
+        let original = Counted {
+            value: 2,
+            clones: &clones,
+        };
+
+        let borrowed = Borrowed(&original);
+        assert!(borrowed.clone().is_borrowed());
+        assert_eq!(clones.get(), 0);
+
+        let owned = Owned(original.clone());
+        assert!(owned.clone().is_owned());
+        assert_eq!(clones.get(), 2);
+    }
+}
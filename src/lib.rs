@@ -4,6 +4,18 @@
 //!
 //! This library provides educational reimplementations of Rust's core types
 //! to help understand how they work under the hood.
+//!
+//! # Miri
+//!
+//! [`Vec0`], [`Box0`] and [`Rc0`] are built on raw pointers, so a test that
+//! passes can still hide undefined behavior. [Miri](https://github.com/rust-lang/miri)
+//! interprets the tests and reports use-after-free, out-of-bounds access and
+//! invalid pointer provenance. CI runs it on every push:
+//!
+//! ```text
+//! rustup +nightly component add miri
+//! cargo +nightly miri test --lib --test miri_compat
+//! ```
 
 pub mod option;
 pub mod result;
@@ -12,12 +24,16 @@ pub mod vec;
 pub mod cell;
 pub mod refcell;
 pub mod rc;
+pub mod btree_map;
+pub mod cow;
 
 // Re-export main types for convenience
 pub use option::Option0;
 pub use result::Result0;
-pub use r#box::Box0;
-pub use vec::{Vec0, IntoIter};
+pub use r#box::{Box0, AllocError};
+pub use vec::{Vec0, IntoIter, IterMut, Drain, ExtractIf, TryReserveError};
 pub use cell::Cell0;
-pub use refcell::{RefCell0, Ref, RefMut, BorrowError, BorrowMutError};
-pub use rc::{Rc0, Weak0};
\ No newline at end of file
+pub use refcell::{RefCell0, Ref, RefMut, BorrowError, BorrowMutError, map_borrow, map_borrow_mut, iter_ref, RefVecIter};
+pub use rc::{Rc0, Weak0};
+pub use btree_map::BTreeMap0;
+pub use cow::Cow0;
\ No newline at end of file
@@ -1,5 +1,7 @@
 //! Option0 - Educational reimplementation of Option<T>
 
+use crate::result::Result0;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Option0<T> {
     Some(T),
@@ -41,6 +43,24 @@ impl<T> Option0<T> {
         }
     }
 
+    /// Returns the contained value, panicking with `msg` if [`None`].
+    /// Prefer it over [`unwrap`](Option0::unwrap): the message says why a value was expected.
+    /// ```
+    /// use rustlib::option::{Option0, Some, None};
+    /// assert_eq!(Some(42).expect("answer should be known"), 42);
+    /// ```
+    /// ```should_panic
+    /// use rustlib::option::{Option0, None};
+    /// let x: Option0<i32> = None;
+    /// x.expect("answer should be known"); // panics: "answer should be known: called expect on a None value"
+    /// ```
+    pub fn expect(self, msg: &str) -> T {
+        match self {
+            Some(val) => val,
+            None => panic!("{}: called expect on a None value", msg),
+        }
+    }
+
     /// Returns the contained value or a default.
     /// ```
     /// use rustlib::option::{Option0, Some, None};
@@ -67,6 +87,23 @@ impl<T> Option0<T> {
         }
     }
 
+    /// Returns the contained value, or `T::default()` if [`None`].
+    /// Unlike `unwrap_or(T::default())`, the default is only built when needed.
+    /// ```
+    /// use rustlib::option::{Option0, Some, None};
+    /// assert_eq!(Some(42).unwrap_or_default(), 42);
+    /// assert_eq!(None::<String>.unwrap_or_default(), "");
+    /// ```
+    pub fn unwrap_or_default(self) -> T
+    where
+        T: Default,
+    {
+        match self {
+            Some(val) => val,
+            None => T::default(),
+        }
+    }
+
     /// Maps a [`Option0<T>`] to [`Option0<U>`] by applying a function.
     /// ```
     /// use rustlib::option::{Option0, Some, None};
@@ -80,6 +117,34 @@ impl<T> Option0<T> {
         }
     }
 
+    /// Applies `f` to the contained value, or returns `default` if [`None`].
+    /// Shorter than `map(f).unwrap_or(default)`.
+    /// ```
+    /// use rustlib::option::{Option0, Some, None};
+    /// assert_eq!(Some("abc").map_or(0, |s| s.len()), 3);
+    /// assert_eq!(None::<&str>.map_or(0, |s| s.len()), 0);
+    /// ```
+    pub fn map_or<U, F: FnOnce(T) -> U>(self, default: U, f: F) -> U {
+        match self {
+            Some(x) => f(x),
+            None => default,
+        }
+    }
+
+    /// Applies `f` to the contained value, or computes a default if [`None`].
+    /// The lazy form of [`map_or`](Option0::map_or): `default` only runs for [`None`].
+    /// ```
+    /// use rustlib::option::{Option0, Some, None};
+    /// assert_eq!(Some(4).map_or_else(|| -1, |x| x * 2), 8);
+    /// assert_eq!(None::<i32>.map_or_else(|| -1, |x| x * 2), -1);
+    /// ```
+    pub fn map_or_else<U, D: FnOnce() -> U, F: FnOnce(T) -> U>(self, default: D, f: F) -> U {
+        match self {
+            Some(x) => f(x),
+            None => default(),
+        }
+    }
+
     /// Applies a function that returns a [`Option0`].
     /// ```
     /// use rustlib::option::{Option0, Some, None};
@@ -93,6 +158,20 @@ impl<T> Option0<T> {
         }
     }
 
+    /// Combines two options with `f` if both are [`Some`], otherwise returns [`None`].
+    /// Shorter than `zip(a, b).map(|(x, y)| f(x, y))`.
+    /// ```
+    /// use rustlib::option::{Option0, Some, None};
+    /// assert_eq!(Some(2).zip_with(Some(3), |a, b| a * b), Some(6));
+    /// assert_eq!(Some(2).zip_with(None, |a, b: i32| a * b), None);
+    /// ```
+    pub fn zip_with<U, R, F: FnOnce(T, U) -> R>(self, other: Option0<U>, f: F) -> Option0<R> {
+        match (self, other) {
+            (Some(x), Some(y)) => Some(f(x, y)),
+            _ => None,
+        }
+    }
+
     /// Returns [`None`] if predicate returns `false`.
     /// ```
     /// use rustlib::option::{Option0, Some, None};
@@ -106,6 +185,22 @@ impl<T> Option0<T> {
         }
     }
 
+    /// Calls `f` with a reference to the contained value, if any, and returns
+    /// the option unchanged. Handy for logging in the middle of a chain.
+    /// ```
+    /// use rustlib::option::{Option0, Some, None};
+    /// let x = Some(4)
+    ///     .inspect(|v| println!("got {v}"))
+    ///     .map(|v| v * 2);
+    /// assert_eq!(x, Some(8));
+    /// ```
+    pub fn inspect<F: FnOnce(&T)>(self, f: F) -> Option0<T> {
+        if let Some(x) = &self {
+            f(x);
+        }
+        self
+    }
+
     /// Converts from `&Option0<T>` to `Option0<&T>`.
     /// ```
     /// use rustlib::option::{Option0, Some, None};
@@ -119,6 +214,49 @@ impl<T> Option0<T> {
         }
     }
 
+    /// Converts from `&mut Option0<T>` to `Option0<&mut T>`,
+    /// so the value can be changed in place without taking it out.
+    /// ```
+    /// use rustlib::option::{Option0, Some, None};
+    /// let mut x = Some(2);
+    /// if let Some(v) = x.as_mut() {
+    ///     *v += 40;
+    /// }
+    /// assert_eq!(x, Some(42));
+    /// ```
+    pub fn as_mut(&mut self) -> Option0<&mut T> {
+        match self {
+            Some(x) => Option0::Some(x),
+            None => Option0::None,
+        }
+    }
+
+    /// Returns an iterator over the contained value: one item for [`Some`],
+    /// none for [`None`].
+    /// ```
+    /// use rustlib::option::{Option0, Some, None};
+    /// assert_eq!(Some(4).iter().count(), 1);
+    /// assert_eq!(None::<i32>.iter().count(), 0);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.as_ref(),
+        }
+    }
+
+    /// Returns an iterator over a mutable reference to the contained value.
+    /// ```
+    /// use rustlib::option::{Option0, Some, None};
+    /// let mut x = Some(4);
+    /// x.iter_mut().for_each(|v| *v += 1);
+    /// assert_eq!(x, Some(5));
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            inner: self.as_mut(),
+        }
+    }
+
     /// Takes the value out, leaving [`None`] in its place.
     /// ```
     /// use rustlib::option::{Option0, Some, None};
@@ -130,6 +268,17 @@ impl<T> Option0<T> {
         std::mem::replace(self, None)
     }
 
+    /// Puts `value` in, returning whatever was there before.
+    /// ```
+    /// use rustlib::option::{Option0, Some, None};
+    /// let mut x = Some(1);
+    /// assert_eq!(x.replace(2), Some(1));
+    /// assert_eq!(x, Some(2));
+    /// ```
+    pub fn replace(&mut self, value: T) -> Option0<T> {
+        std::mem::replace(self, Some(value))
+    }
+
     /// Returns the option if [`Some`], otherwise returns `other`.
     /// ```
     /// use rustlib::option::{Option0, Some, None};
@@ -143,6 +292,21 @@ impl<T> Option0<T> {
         }
     }
 
+    /// Returns whichever option is [`Some`] if exactly one of them is,
+    /// otherwise [`None`].
+    /// ```
+    /// use rustlib::option::{Option0, Some, None};
+    /// assert_eq!(Some(1).xor(None), Some(1));
+    /// assert_eq!(None.xor(Some(2)), Some(2));
+    /// assert_eq!(Some(1).xor(Some(2)), None);
+    /// ```
+    pub fn xor(self, other: Option0<T>) -> Option0<T> {
+        match (self, other) {
+            (Some(x), None) | (None, Some(x)) => Some(x),
+            _ => None,
+        }
+    }
+
     /// Returns the option if [`Some`], otherwise calls `f`.
     /// ```
     /// use rustlib::option::{Option0, Some, None};
@@ -187,6 +351,216 @@ impl<T> Option0<Option0<T>> {
     }
 }
 
+impl<T> Option0<&T> {
+    /// Maps an `Option0<&T>` to an `Option0<T>` by copying the value.
+    /// ```
+    /// use rustlib::option::{Option0, Some, None};
+    /// let x = 42;
+    /// assert_eq!(Some(&x).copied(), Some(42));
+    /// ```
+    pub fn copied(self) -> Option0<T>
+    where
+        T: Copy,
+    {
+        match self {
+            Some(&x) => Some(x),
+            None => None,
+        }
+    }
+
+    /// Maps an `Option0<&T>` to an `Option0<T>` by cloning the value.
+    /// The reference itself is left alone: the original keeps its value.
+    /// ```
+    /// use rustlib::option::{Option0, Some, None};
+    /// let s = String::from("hi");
+    /// assert_eq!(Some(&s).cloned(), Some(String::from("hi")));
+    /// ```
+    pub fn cloned(self) -> Option0<T>
+    where
+        T: Clone,
+    {
+        match self {
+            Some(x) => Some(x.clone()),
+            None => None,
+        }
+    }
+}
+
+impl<T> Option0<&mut T> {
+    /// Maps an `Option0<&mut T>` to an `Option0<T>` by copying the value.
+    /// ```
+    /// use rustlib::option::{Option0, Some, None};
+    /// let mut x = 42;
+    /// assert_eq!(Some(&mut x).copied(), Some(42));
+    /// ```
+    pub fn copied(self) -> Option0<T>
+    where
+        T: Copy,
+    {
+        match self {
+            Some(&mut x) => Some(x),
+            None => None,
+        }
+    }
+
+    /// Maps an `Option0<&mut T>` to an `Option0<T>` by cloning the value.
+    /// ```
+    /// use rustlib::option::{Option0, Some, None};
+    /// let mut s = String::from("hi");
+    /// assert_eq!(Some(&mut s).cloned(), Some(String::from("hi")));
+    /// ```
+    pub fn cloned(self) -> Option0<T>
+    where
+        T: Clone,
+    {
+        match self {
+            Some(x) => Some(x.clone()),
+            None => None,
+        }
+    }
+}
+
+impl<T, E> Option0<Result0<T, E>> {
+    /// Turns an option of a result into a result of an option:
+    /// `Some(Ok(v))` becomes `Ok(Some(v))`, `Some(Err(e))` becomes `Err(e)`
+    /// and [`None`] becomes `Ok(None)`. The inverse of [`Result0::transpose`].
+    /// ```
+    /// use rustlib::option::{Option0, Some, None};
+    /// use rustlib::result::{Result0, Ok, Err};
+    /// let x: Option0<Result0<i32, &str>> = Some(Ok(5));
+    /// assert_eq!(x.transpose(), Ok(Some(5)));
+    /// ```
+    pub fn transpose(self) -> Result0<Option0<T>, E> {
+        match self {
+            Some(Result0::Ok(x)) => Result0::Ok(Some(x)),
+            Some(Result0::Err(e)) => Result0::Err(e),
+            None => Result0::Ok(None),
+        }
+    }
+}
+
+// ============================================================================
+// Iterators - an option is a collection of zero or one element
+// ============================================================================
+
+/// Iterator over a reference to the value of an [`Option0`].
+/// Created by [`Option0::iter`] or by iterating over `&Option0<T>`.
+pub struct Iter<'a, T> {
+    // Handed out on the first `next`, then left as `None`
+    inner: Option0<&'a T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> std::option::Option<&'a T> {
+        match self.inner.take() {
+            Some(x) => std::option::Option::Some(x),
+            None => std::option::Option::None,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, std::option::Option<usize>) {
+        let n = self.inner.is_some() as usize;
+        (n, std::option::Option::Some(n))
+    }
+}
+
+/// Iterator over a mutable reference to the value of an [`Option0`].
+/// Created by [`Option0::iter_mut`] or by iterating over `&mut Option0<T>`.
+pub struct IterMut<'a, T> {
+    inner: Option0<&'a mut T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> std::option::Option<&'a mut T> {
+        match self.inner.take() {
+            Some(x) => std::option::Option::Some(x),
+            None => std::option::Option::None,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, std::option::Option<usize>) {
+        let n = self.inner.is_some() as usize;
+        (n, std::option::Option::Some(n))
+    }
+}
+
+/// Iterator that moves the value out of an [`Option0`].
+/// Created by iterating over an `Option0<T>` by value.
+pub struct IntoIter<T> {
+    inner: Option0<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> std::option::Option<T> {
+        match self.inner.take() {
+            Some(x) => std::option::Option::Some(x),
+            None => std::option::Option::None,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, std::option::Option<usize>) {
+        let n = self.inner.is_some() as usize;
+        (n, std::option::Option::Some(n))
+    }
+}
+
+/// Iterating over an [`Option0`] by value yields the value, if any.
+/// ```
+/// use rustlib::option::{Option0, Some, None};
+/// let words: Vec<String> = Some(String::from("hi")).into_iter().collect();
+/// assert_eq!(words, vec!["hi"]);
+/// ```
+impl<T> IntoIterator for Option0<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { inner: self }
+    }
+}
+
+/// Iterating over `&Option0<T>` borrows the value, if any.
+/// ```
+/// use rustlib::option::{Option0, Some, None};
+/// let x = Some(3);
+/// for v in &x {
+///     assert_eq!(*v, 3);
+/// }
+/// assert_eq!(x, Some(3));
+/// ```
+impl<'a, T> IntoIterator for &'a Option0<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+/// Iterating over `&mut Option0<T>` gives mutable access to the value, if any.
+/// ```
+/// use rustlib::option::{Option0, Some, None};
+/// let mut x = Some(3);
+/// for v in &mut x {
+///     *v *= 2;
+/// }
+/// assert_eq!(x, Some(6));
+/// ```
+impl<'a, T> IntoIterator for &'a mut Option0<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
 /// Zips two options together into a tuple.
 /// Returns [`None`] if either option is [`None`].
 /// ```
@@ -228,6 +602,25 @@ mod tests {
         x.unwrap();
     }
 
+    #[test]
+    fn test_expect() {
+        assert_eq!(Some(42).expect("should be some"), 42);
+    }
+
+    #[test]
+    #[should_panic(expected = "custom message: called expect on a None value")]
+    fn test_expect_panic_message() {
+        let x: Option0<i32> = None;
+        x.expect("custom message");
+    }
+
+    #[test]
+    #[should_panic(expected = "config file must be loaded")]
+    fn test_expect_none_panics() {
+        let x: Option0<i32> = None;
+        x.expect("config file must be loaded");
+    }
+
     #[test]
     fn test_unwrap_or() {
         assert_eq!(Some(42).unwrap_or(0), 42);
@@ -240,6 +633,69 @@ mod tests {
         assert_eq!(None.unwrap_or_else(|| 100), 100);
     }
 
+    #[test]
+    fn test_inspect() {
+        let mut calls = 0;
+        let x = Some(String::from("hi")).inspect(|s| {
+            calls += 1;
+            assert_eq!(s, "hi");
+        });
+        assert_eq!(x, Some(String::from("hi")));
+        assert_eq!(calls, 1);
+
+        let y: Option0<String> = None.inspect(|_| calls += 1);
+        assert_eq!(y, None);
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_map_or() {
+        assert_eq!(Some(String::from("abc")).map_or(0, |s| s.len()), 3);
+        assert_eq!(None::<String>.map_or(0, |s| s.len()), 0);
+        assert_eq!(
+            None::<i32>.map_or(0, |_| unreachable!("map called for None")),
+            0
+        );
+    }
+
+    #[test]
+    fn test_map_or_else() {
+        let some = Some(5).map_or_else(|| unreachable!("default called for Some"), |x| x * 2);
+        assert_eq!(some, 10);
+
+        let none = None::<i32>.map_or_else(|| -1, |_| unreachable!("map called for None"));
+        assert_eq!(none, -1);
+    }
+
+    #[test]
+    fn test_unwrap_or_default() {
+        assert_eq!(Some(String::from("hi")).unwrap_or_default(), "hi");
+        assert_eq!(None::<String>.unwrap_or_default(), "");
+    }
+
+    #[test]
+    fn test_unwrap_or_default_is_lazy() {
+        use std::cell::Cell;
+
+        thread_local! {
+            static DEFAULTS: Cell<usize> = const { Cell::new(0) };
+        }
+
+        #[derive(Debug, PartialEq)]
+        struct Counted(i32);
+        impl Default for Counted {
+            fn default() -> Counted {
+                DEFAULTS.with(|count| count.set(count.get() + 1));
+                Counted(0)
+            }
+        }
+
+        assert_eq!(Some(Counted(7)).unwrap_or_default(), Counted(7));
+        assert_eq!(DEFAULTS.with(Cell::get), 0);
+        assert_eq!(None::<Counted>.unwrap_or_default(), Counted(0));
+        assert_eq!(DEFAULTS.with(Cell::get), 1);
+    }
+
     #[test]
     fn test_map() {
         assert_eq!(Some(10).map(|x| x * 2), Some(20));
@@ -282,6 +738,16 @@ mod tests {
         assert_eq!(x, Some(String::from("hello"))); // x still valid
     }
 
+    #[test]
+    fn test_as_mut() {
+        let mut x = Some(String::from("hello"));
+        x.as_mut().map(|s| s.push('!'));
+        assert_eq!(x, Some(String::from("hello!")));
+
+        let mut y: Option0<String> = None;
+        assert!(y.as_mut().is_none());
+    }
+
     #[test]
     fn test_take() {
         let mut x = Some(42);
@@ -292,6 +758,27 @@ mod tests {
         assert_eq!(y.take(), None);
     }
 
+    #[test]
+    fn test_xor() {
+        assert_eq!(None::<i32>.xor(None), None);
+        assert_eq!(Some(1).xor(None), Some(1));
+        assert_eq!(None.xor(Some(2)), Some(2));
+        assert_eq!(Some(1).xor(Some(2)), None);
+    }
+
+    #[test]
+    fn test_replace() {
+        let mut x: Option0<String> = None;
+        assert_eq!(x.replace(String::from("first")), None);
+        assert_eq!(x, Some(String::from("first")));
+
+        assert_eq!(
+            x.replace(String::from("second")),
+            Some(String::from("first"))
+        );
+        assert_eq!(x, Some(String::from("second")));
+    }
+
     #[test]
     fn test_or() {
         assert_eq!(Some(1).or(Some(2)), Some(1));
@@ -338,6 +825,117 @@ mod tests {
         assert_eq!(none_outer.flatten(), None);
     }
 
+    #[test]
+    fn test_copied_cloned() {
+        let x = 7;
+        assert_eq!(Some(&x).copied(), Some(7));
+        assert_eq!(None::<&i32>.copied(), None);
+
+        let s = String::from("hello");
+        let cloned = Some(&s).cloned();
+        assert_eq!(cloned, Some(String::from("hello")));
+        assert_eq!(s, "hello"); // still usable
+        assert_eq!(None::<&String>.cloned(), None);
+    }
+
+    #[test]
+    fn test_copied_cloned_mut() {
+        let mut x = Some(7);
+        let copied = x.as_mut().copied();
+        assert_eq!(copied, Some(7));
+
+        let mut s = Some(String::from("hello"));
+        let cloned = s.as_mut().cloned();
+        if let Some(inner) = s.as_mut() {
+            inner.push('!');
+        }
+        // The clone is independent of the original
+        assert_eq!(cloned, Some(String::from("hello")));
+        assert_eq!(s, Some(String::from("hello!")));
+        assert_eq!(None::<&mut String>.cloned(), None);
+    }
+
+    #[test]
+    fn test_zip_with() {
+        let mut calls = 0;
+        let mut join = |a: String, b: &str| {
+            calls += 1;
+            a + b
+        };
+        assert_eq!(
+            Some(String::from("a")).zip_with(Some("b"), &mut join),
+            Some(String::from("ab"))
+        );
+        assert_eq!(Some(String::from("a")).zip_with(None, &mut join), None);
+        assert_eq!(None.zip_with(Some("b"), &mut join), None);
+        assert_eq!(None.zip_with(None, &mut join), None);
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_iter() {
+        let some = Some(String::from("a"));
+        let none: Option0<String> = None;
+        assert_eq!(some.iter().count(), 1);
+        assert_eq!(none.iter().count(), 0);
+        assert_eq!(some.iter().size_hint(), (1, std::option::Option::Some(1)));
+
+        let lens: Vec<usize> = some.iter().map(|s| s.len()).collect();
+        assert_eq!(lens, vec![1]);
+
+        // Chains with other iterators, skipping the None
+        let all: Vec<&String> = some.iter().chain(none.iter()).collect();
+        assert_eq!(all, vec!["a"]);
+    }
+
+    #[test]
+    fn test_iter_mut() {
+        let mut some = Some(String::from("a"));
+        some.iter_mut().for_each(|s| s.push('!'));
+        for s in &mut some {
+            s.push('?');
+        }
+        assert_eq!(some, Some(String::from("a!?")));
+
+        let mut none: Option0<String> = None;
+        assert_eq!(none.iter_mut().count(), 0);
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let mut seen = Vec::new();
+        for s in Some(String::from("owned")) {
+            seen.push(s);
+        }
+        assert_eq!(seen, vec!["owned"]);
+
+        let none: Option0<String> = None;
+        assert_eq!(none.into_iter().count(), 0);
+
+        // Flattening a list of options keeps only the values
+        let values: Vec<i32> = vec![Some(1), None, Some(3)].into_iter().flatten().collect();
+        assert_eq!(values, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_transpose() {
+        let some_ok: Option0<Result0<i32, &str>> = Some(Result0::Ok(5));
+        assert_eq!(some_ok.transpose(), Result0::Ok(Some(5)));
+
+        let some_err: Option0<Result0<i32, &str>> = Some(Result0::Err("bad"));
+        assert_eq!(some_err.transpose(), Result0::Err("bad"));
+
+        let none: Option0<Result0<i32, &str>> = None;
+        assert_eq!(none.transpose(), Result0::Ok(None));
+
+        // Transposing back undoes it, for every form
+        let forms: [Option0<Result0<i32, &str>>; 3] =
+            [Some(Result0::Ok(5)), Some(Result0::Err("bad")), None];
+        for x in forms {
+            assert_eq!(x.clone().transpose().transpose(), x);
+        }
+    }
+
     #[test]
     fn test_clone() {
         let x = Some(42);
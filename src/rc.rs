@@ -2,7 +2,7 @@
 
 use std::cell::Cell;
 use std::mem::ManuallyDrop;
-use std::ops::Deref;
+use std::ops::{Deref, Index};
 
 struct RcInner<T> {
     strong_count: Cell<usize>,
@@ -76,6 +76,20 @@ impl<T> Deref for Rc0<T> {
     }
 }
 
+impl<T: Index<usize>> Index<usize> for Rc0<T> {
+    type Output = T::Output;
+
+    fn index(&self, index: usize) -> &T::Output {
+        &(**self)[index]
+    }
+}
+
+impl<T> AsRef<T> for Rc0<T> {
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
 impl<T> Drop for Rc0<T> {
     fn drop(&mut self) {
         let inner = unsafe { &*self.ptr };
@@ -148,6 +162,24 @@ impl<T> Drop for Weak0<T> {
     }
 }
 
+// Never shows the value: reading it would mean upgrading, and once the
+// last Rc0 is gone there is no value left to show
+impl<T> std::fmt::Debug for Weak0<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.strong_count() == 0 {
+            write!(f, "Weak0(<orphan>)")
+        } else {
+            write!(f, "Weak0(weak)")
+        }
+    }
+}
+
+impl<T> std::fmt::Display for Weak0<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self, f)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -262,6 +294,18 @@ mod tests {
         assert_eq!(format!("{:?}", rc), "Rc0(42)");
     }
 
+    #[test]
+    fn test_weak_debug_display() {
+        let rc = Rc0::new(42);
+        let weak = Rc0::downgrade(&rc);
+        assert_eq!(format!("{:?}", weak), "Weak0(weak)");
+        assert_eq!(weak.to_string(), "Weak0(weak)");
+
+        drop(rc);
+        assert_eq!(format!("{:?}", weak), "Weak0(<orphan>)");
+        assert_eq!(weak.to_string(), "Weak0(<orphan>)");
+    }
+
     #[test]
     fn test_multiple_weak_refs() {
         let rc = Rc0::new(42);
@@ -281,6 +325,25 @@ mod tests {
         assert_eq!(Rc0::weak_count(&rc), 0);
     }
 
+    #[test]
+    fn test_index() {
+        let rc = Rc0::new(vec![10, 20, 30]);
+        let rc2 = rc.clone();
+        assert_eq!(rc[0], 10);
+        assert_eq!(rc2[2], 30);
+    }
+
+    #[test]
+    fn test_as_ref() {
+        fn total<R: AsRef<Vec<i32>>>(r: R) -> i32 {
+            r.as_ref().iter().sum()
+        }
+
+        let rc = Rc0::new(vec![1, 2, 3]);
+        assert_eq!(total(rc.clone()), 6);
+        assert_eq!(Rc0::strong_count(&rc), 1);
+    }
+
     #[test]
     fn test_drop_order() {
         use std::sync::Arc;
@@ -1,6 +1,8 @@
 //! RefCell0 - Educational reimplementation of RefCell<T>
 
+use crate::vec::Vec0;
 use std::cell::{Cell, UnsafeCell};
+use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
 
 pub struct RefCell0<T> {
@@ -8,12 +10,20 @@ pub struct RefCell0<T> {
     value: UnsafeCell<T>,
 }
 
-pub struct Ref<'a, T> {
-    refcell: &'a RefCell0<T>,
+// Guards keep a reference to the value and to the borrow counter separately,
+// so `map` can narrow the value to a field while still releasing the
+// borrow on the original cell when dropped.
+pub struct Ref<'a, T: ?Sized> {
+    value: &'a T,
+    borrow_count: &'a Cell<isize>,
 }
 
-pub struct RefMut<'a, T> {
-    refcell: &'a RefCell0<T>,
+pub struct RefMut<'a, T: ?Sized> {
+    // Raw pointer rather than `&'a mut T`: moving a `&mut` asserts uniqueness,
+    // which would invalidate pointers derived from it in `map`
+    value: *mut T,
+    borrow_count: &'a Cell<isize>,
+    _marker: PhantomData<&'a mut T>,
 }
 
 #[derive(Debug)]
@@ -44,7 +54,10 @@ impl<T> RefCell0<T> {
             Err(BorrowError)
         } else {
             self.borrow_count.set(count + 1);
-            Ok(Ref { refcell: self })
+            Ok(Ref {
+                value: unsafe { &*self.value.get() },
+                borrow_count: &self.borrow_count,
+            })
         }
     }
 
@@ -53,7 +66,11 @@ impl<T> RefCell0<T> {
             Err(BorrowMutError)
         } else {
             self.borrow_count.set(-1);
-            Ok(RefMut { refcell: self })
+            Ok(RefMut {
+                value: self.value.get(),
+                borrow_count: &self.borrow_count,
+                _marker: PhantomData,
+            })
         }
     }
 
@@ -70,39 +87,188 @@ impl<T> RefCell0<T> {
     }
 }
 
-impl<T> Deref for Ref<'_, T> {
+impl<'a, T: ?Sized> Ref<'a, T> {
+    /// Makes a new [`Ref`] for a component of the borrowed data.
+    /// The cell stays immutably borrowed until the new guard is dropped.
+    /// ```
+    /// use rustlib::refcell::{RefCell0, Ref};
+    /// let cell = RefCell0::new((1, String::from("hello")));
+    /// let name = Ref::map(cell.borrow(), |pair| &pair.1);
+    /// assert_eq!(*name, "hello");
+    /// ```
+    pub fn map<U: ?Sized, F: FnOnce(&T) -> &U>(orig: Ref<'a, T>, f: F) -> Ref<'a, U> {
+        // Project first: if `f` panics, `orig` is still alive and its
+        // Drop releases the borrow during unwinding
+        let value = f(orig.value);
+        let borrow_count = orig.borrow_count;
+        // The new guard takes over the borrow, so don't release it here
+        std::mem::forget(orig);
+        Ref {
+            value,
+            borrow_count,
+        }
+    }
+}
+
+impl<T: ?Sized> Deref for Ref<'_, T> {
     type Target = T;
     fn deref(&self) -> &T {
-        unsafe { &*self.refcell.value.get() }
+        self.value
     }
 }
 
-impl<T> Drop for Ref<'_, T> {
+impl<T: ?Sized> Drop for Ref<'_, T> {
     fn drop(&mut self) {
-        let count = self.refcell.borrow_count.get();
-        self.refcell.borrow_count.set(count - 1);
+        let count = self.borrow_count.get();
+        self.borrow_count.set(count - 1);
+    }
+}
+
+impl<'a, T: ?Sized> RefMut<'a, T> {
+    /// Makes a new [`RefMut`] for a component of the borrowed data.
+    /// The cell stays mutably borrowed until the new guard is dropped.
+    /// ```
+    /// use rustlib::refcell::{RefCell0, RefMut};
+    /// let cell = RefCell0::new((1, String::from("hello")));
+    /// {
+    ///     let mut name = RefMut::map(cell.borrow_mut(), |pair| &mut pair.1);
+    ///     name.push_str(" world");
+    /// }
+    /// assert_eq!(cell.borrow().1, "hello world");
+    /// ```
+    pub fn map<U: ?Sized, F: FnOnce(&mut T) -> &mut U>(orig: RefMut<'a, T>, f: F) -> RefMut<'a, U> {
+        // Project first, so a panicking `f` still drops `orig` and releases the borrow
+        let value = f(unsafe { &mut *orig.value });
+        let borrow_count = orig.borrow_count;
+        // The new guard takes over the borrow, so don't release it here
+        std::mem::forget(orig);
+        RefMut {
+            value,
+            borrow_count,
+            _marker: PhantomData,
+        }
     }
 }
 
-impl<T> Deref for RefMut<'_, T> {
+impl<T: ?Sized> Deref for RefMut<'_, T> {
     type Target = T;
     fn deref(&self) -> &T {
-        unsafe { &*self.refcell.value.get() }
+        unsafe { &*self.value }
     }
 }
 
-impl<T> DerefMut for RefMut<'_, T> {
+impl<T: ?Sized> DerefMut for RefMut<'_, T> {
     fn deref_mut(&mut self) -> &mut T {
-        unsafe { &mut *self.refcell.value.get() }
+        unsafe { &mut *self.value }
     }
 }
 
-impl<T> Drop for RefMut<'_, T> {
+impl<T: ?Sized> Drop for RefMut<'_, T> {
     fn drop(&mut self) {
-        self.refcell.borrow_count.set(0);
+        self.borrow_count.set(0);
+    }
+}
+
+impl<T: ?Sized + std::fmt::Display> std::fmt::Display for Ref<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<T: ?Sized + std::fmt::Debug> std::fmt::Debug for Ref<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<T: ?Sized + std::fmt::Display> std::fmt::Display for RefMut<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<T: ?Sized + std::fmt::Debug> std::fmt::Debug for RefMut<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+/// Free-function form of [`Ref::map`].
+/// ```
+/// use rustlib::refcell::{RefCell0, map_borrow};
+/// let cell = RefCell0::new(vec![1, 2, 3]);
+/// let first = map_borrow(cell.borrow(), |v| &v[0]);
+/// assert_eq!(*first, 1);
+/// ```
+pub fn map_borrow<'a, T: ?Sized, U: ?Sized, F>(r: Ref<'a, T>, f: F) -> Ref<'a, U>
+where
+    F: FnOnce(&T) -> &U,
+{
+    Ref::map(r, f)
+}
+
+/// Free-function form of [`RefMut::map`].
+/// ```
+/// use rustlib::refcell::{RefCell0, map_borrow_mut};
+/// let cell = RefCell0::new(vec![1, 2, 3]);
+/// *map_borrow_mut(cell.borrow_mut(), |v| &mut v[0]) = 10;
+/// assert_eq!(cell.borrow()[0], 10);
+/// ```
+pub fn map_borrow_mut<'a, T: ?Sized, U: ?Sized, F>(r: RefMut<'a, T>, f: F) -> RefMut<'a, U>
+where
+    F: FnOnce(&mut T) -> &mut U,
+{
+    RefMut::map(r, f)
+}
+
+/// Iterator over the elements of a borrowed [`Vec0`]. Created by [`iter_ref`].
+///
+/// It owns the `Ref` to the vector, so the cell stays borrowed while iterating.
+/// Each item is itself a [`Ref`] to one element rather than a plain `&'a T`:
+/// a plain reference could outlive the iterator, and once the iterator's guard
+/// released the borrow, `borrow_mut` would succeed while it was still alive.
+/// Every item counts as one more shared borrow, exactly like [`Ref::map`].
+pub struct RefVecIter<'a, T> {
+    vec: Ref<'a, Vec0<T>>,
+    index: usize,
+}
+
+impl<'a, T> Iterator for RefVecIter<'a, T> {
+    type Item = Ref<'a, T>;
+
+    fn next(&mut self) -> Option<Ref<'a, T>> {
+        // Copying the `&'a Vec0<T>` out of the guard gives element references
+        // with the full `'a` lifetime, no unsafe needed
+        let vec: &'a Vec0<T> = self.vec.value;
+        let value = vec.as_slice().get(self.index)?;
+        self.index += 1;
+
+        let count = self.vec.borrow_count.get();
+        self.vec.borrow_count.set(count + 1);
+        Some(Ref {
+            value,
+            borrow_count: self.vec.borrow_count,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.vec.len() - self.index;
+        (remaining, Some(remaining))
     }
 }
 
+/// Turns a borrow of a [`Vec0`] into an iterator of borrows of its elements.
+/// ```
+/// use rustlib::refcell::{RefCell0, iter_ref};
+/// use rustlib::vec0;
+/// let cell = RefCell0::new(vec0![1, 2, 3]);
+/// let sum: i32 = iter_ref(cell.borrow()).map(|x| *x).sum();
+/// assert_eq!(sum, 6);
+/// ```
+pub fn iter_ref<T>(r: Ref<'_, Vec0<T>>) -> RefVecIter<'_, T> {
+    RefVecIter { vec: r, index: 0 }
+}
+
 impl<T: Clone> Clone for RefCell0<T> {
     fn clone(&self) -> RefCell0<T> {
         RefCell0::new(self.borrow().clone())
@@ -242,6 +408,103 @@ mod tests {
         assert_eq!(format!("{:?}", cell), "RefCell0(<borrowed>)");
     }
 
+    #[test]
+    fn test_guard_formatting() {
+        let cell = RefCell0::new(String::from("hello"));
+        {
+            let r = cell.borrow();
+            assert_eq!(format!("{}", r), "hello");
+            assert_eq!(format!("{:?}", r), "\"hello\"");
+        }
+
+        let m = cell.borrow_mut();
+        assert_eq!(format!("{}", m), "hello");
+        assert_eq!(format!("{:?}", m), "\"hello\"");
+    }
+
+    #[test]
+    fn test_ref_map() {
+        let cell = RefCell0::new((1, String::from("hello")));
+        let name = Ref::map(cell.borrow(), |pair| &pair.1);
+        assert_eq!(*name, "hello");
+
+        // Mapped guard still holds the shared borrow
+        assert!(cell.try_borrow_mut().is_err());
+        drop(name);
+        assert!(cell.try_borrow_mut().is_ok());
+    }
+
+    #[test]
+    fn test_ref_mut_map() {
+        let cell = RefCell0::new((1, String::from("hello")));
+        let mut name = RefMut::map(cell.borrow_mut(), |pair| &mut pair.1);
+        name.push_str(" world");
+
+        // Mapped guard still holds the exclusive borrow
+        assert!(cell.try_borrow().is_err());
+        drop(name);
+        assert_eq!(cell.borrow().1, "hello world");
+    }
+
+    #[test]
+    fn test_map_panic_releases_borrow() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let cell = RefCell0::new(vec![1, 2, 3]);
+
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            RefMut::map(cell.borrow_mut(), |v: &mut Vec<i32>| -> &mut i32 {
+                panic!("projection failed: {}", v.len())
+            });
+        }));
+        assert!(result.is_err());
+        // The exclusive borrow was released while unwinding
+        assert!(cell.try_borrow().is_ok());
+
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            Ref::map(cell.borrow(), |v: &Vec<i32>| -> &i32 {
+                panic!("projection failed: {}", v.len())
+            });
+        }));
+        assert!(result.is_err());
+        // So was the shared one
+        assert!(cell.try_borrow_mut().is_ok());
+    }
+
+    #[test]
+    fn test_map_borrow_free_functions() {
+        let cell = RefCell0::new(vec![1, 2, 3]);
+
+        let tail = map_borrow(cell.borrow(), |v| &v[1..]);
+        assert_eq!(&*tail, &[2, 3]);
+        drop(tail);
+
+        *map_borrow_mut(cell.borrow_mut(), |v| &mut v[0]) = 10;
+        assert_eq!(*cell.borrow(), vec![10, 2, 3]);
+    }
+
+    #[test]
+    fn test_iter_ref() {
+        use crate::vec0;
+
+        let cell = RefCell0::new(vec0![String::from("a"), String::from("b")]);
+        let mut iter = iter_ref(cell.borrow());
+        assert_eq!(iter.size_hint(), (2, Some(2)));
+
+        let first = iter.next().unwrap();
+        assert_eq!(*first, "a");
+        drop(iter);
+
+        // The item still holds a borrow after the iterator is gone
+        assert!(cell.try_borrow_mut().is_err());
+        drop(first);
+        assert!(cell.try_borrow_mut().is_ok());
+
+        let joined: String = iter_ref(cell.borrow()).map(|s| s.clone()).collect();
+        assert_eq!(joined, "ab");
+        assert_eq!(cell.borrow_count.get(), 0);
+    }
+
     #[test]
     fn test_borrow_guard_drop() {
         let cell = RefCell0::new(42);
@@ -4,6 +4,8 @@
 //! use rustlib::result::Result0;
 //! ```
 
+use crate::option::Option0;
+use std::convert::Infallible;
 use std::fmt;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -113,6 +115,36 @@ impl<T, E> Result0<T, E> {
         }
     }
 
+    /// Calls `f` with a reference to the [`Ok`] value, if any, and returns
+    /// the result unchanged. Handy for logging in the middle of a chain.
+    /// ```
+    /// use rustlib::result::{Result0, Ok, Err};
+    /// let x = Ok::<i32, &str>(4)
+    ///     .inspect(|v| println!("got {v}"))
+    ///     .map(|v| v * 2);
+    /// assert_eq!(x, Ok(8));
+    /// ```
+    pub fn inspect<F: FnOnce(&T)>(self, f: F) -> Result0<T, E> {
+        if let Ok(x) = &self {
+            f(x);
+        }
+        self
+    }
+
+    /// Calls `f` with a reference to the [`Err`] value, if any, and returns
+    /// the result unchanged.
+    /// ```
+    /// use rustlib::result::{Result0, Ok, Err};
+    /// let x = Err::<i32, &str>("bad input").inspect_err(|e| eprintln!("failed: {e}"));
+    /// assert_eq!(x, Err("bad input"));
+    /// ```
+    pub fn inspect_err<F: FnOnce(&E)>(self, f: F) -> Result0<T, E> {
+        if let Err(e) = &self {
+            f(e);
+        }
+        self
+    }
+
     /// Applies a function that returns a [`Result0`] to the [`Ok`] value.
     /// ```
     /// use rustlib::result::{Result0, Ok, Err};
@@ -139,6 +171,52 @@ impl<T, E> Result0<T, E> {
         }
     }
 
+    /// Converts from `&mut Result0<T, E>` to `Result0<&mut T, &mut E>`,
+    /// so either value can be changed in place without consuming the result.
+    /// ```
+    /// use rustlib::result::{Result0, Ok, Err};
+    /// let mut x: Result0<i32, String> = Err(String::from("bad"));
+    /// if let Err(e) = x.as_mut() {
+    ///     e.push_str(" input");
+    /// }
+    /// assert_eq!(x, Err(String::from("bad input")));
+    /// ```
+    pub fn as_mut(&mut self) -> Result0<&mut T, &mut E> {
+        match self {
+            Ok(x) => Result0::Ok(x),
+            Err(e) => Result0::Err(e),
+        }
+    }
+
+    /// Returns an iterator over the [`Ok`] value: one item for [`Ok`],
+    /// none for [`Err`].
+    /// ```
+    /// use rustlib::result::{Result0, Ok, Err};
+    /// let x: Result0<i32, &str> = Ok(1);
+    /// let y: Result0<i32, &str> = Err("bad");
+    /// let z: Result0<i32, &str> = Ok(3);
+    /// let all: Vec<&i32> = x.iter().chain(y.iter()).chain(z.iter()).collect();
+    /// assert_eq!(all, vec![&1, &3]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.as_ref().ok(),
+        }
+    }
+
+    /// Returns an iterator over a mutable reference to the [`Ok`] value.
+    /// ```
+    /// use rustlib::result::{Result0, Ok, Err};
+    /// let mut x: Result0<i32, &str> = Ok(4);
+    /// x.iter_mut().for_each(|v| *v += 1);
+    /// assert_eq!(x, Ok(5));
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            inner: self.as_mut().ok(),
+        }
+    }
+
     /// Returns the result if [`Ok`], otherwise returns `other`.
     /// ```
     /// use rustlib::result::{Result0, Ok, Err};
@@ -195,6 +273,217 @@ impl<T, E> Result0<Result0<T, E>, E> {
     }
 }
 
+impl<T, E> Result0<T, E> {
+    /// Returns the [`Ok`] value of a result that can't fail.
+    ///
+    /// [`Infallible`] has no values, so an `Err` of it can never be built and
+    /// the `Err` arm below matches on an empty enum: `match never {}`
+    /// has no cases, yet type-checks as any type. Unlike `unwrap`, nothing
+    /// here can panic, and the compiler proves it.
+    /// ```
+    /// use std::convert::Infallible;
+    /// use rustlib::result::{Result0, Ok};
+    /// let x: Result0<i32, Infallible> = Ok(42);
+    /// assert_eq!(x.into_ok(), 42);
+    /// ```
+    pub fn into_ok(self) -> T
+    where
+        E: Into<Infallible>,
+    {
+        match self.map_err(Into::<Infallible>::into) {
+            Ok(x) => x,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Returns the [`Err`] value of a result that can't succeed.
+    /// The mirror image of [`into_ok`](Result0::into_ok).
+    /// ```
+    /// use std::convert::Infallible;
+    /// use rustlib::result::{Result0, Err};
+    /// let x: Result0<Infallible, &str> = Err("always fails");
+    /// assert_eq!(x.into_err(), "always fails");
+    /// ```
+    pub fn into_err(self) -> E
+    where
+        T: Into<Infallible>,
+    {
+        match self.map(Into::<Infallible>::into) {
+            Ok(never) => match never {},
+            Err(e) => e,
+        }
+    }
+}
+
+impl<T, E> Result0<&T, &E> {
+    /// Maps a `Result0<&T, &E>` to a `Result0<T, E>` by copying either value.
+    /// ```
+    /// use rustlib::result::{Result0, Ok, Err};
+    /// let (x, e) = (42, 7);
+    /// assert_eq!(Ok::<&i32, &i32>(&x).copied(), Ok(42));
+    /// assert_eq!(Err::<&i32, &i32>(&e).copied(), Err(7));
+    /// ```
+    pub fn copied(self) -> Result0<T, E>
+    where
+        T: Copy,
+        E: Copy,
+    {
+        match self {
+            Ok(&x) => Ok(x),
+            Err(&e) => Err(e),
+        }
+    }
+
+    /// Maps a `Result0<&T, &E>` to a `Result0<T, E>` by cloning either value.
+    /// ```
+    /// use rustlib::result::{Result0, Ok, Err};
+    /// let s = String::from("hi");
+    /// assert_eq!(Ok::<&String, &String>(&s).cloned(), Ok(String::from("hi")));
+    /// ```
+    pub fn cloned(self) -> Result0<T, E>
+    where
+        T: Clone,
+        E: Clone,
+    {
+        match self {
+            Ok(x) => Ok(x.clone()),
+            Err(e) => Err(e.clone()),
+        }
+    }
+}
+
+impl<T, E> Result0<Option0<T>, E> {
+    /// Turns a result of an option into an option of a result:
+    /// `Ok(Some(v))` becomes `Some(Ok(v))`, `Ok(None)` becomes [`None`](Option0::None)
+    /// and `Err(e)` becomes `Some(Err(e))`. The inverse of [`Option0::transpose`].
+    /// ```
+    /// use rustlib::option::{Option0, Some, None};
+    /// use rustlib::result::{Result0, Ok, Err};
+    /// let x: Result0<Option0<i32>, &str> = Ok(Some(5));
+    /// assert_eq!(x.transpose(), Some(Ok(5)));
+    /// ```
+    pub fn transpose(self) -> Option0<Result0<T, E>> {
+        match self {
+            Ok(Option0::Some(x)) => Option0::Some(Ok(x)),
+            Ok(Option0::None) => Option0::None,
+            Err(e) => Option0::Some(Err(e)),
+        }
+    }
+}
+
+// ============================================================================
+// Iterators - like an option, a result holds zero or one Ok value
+// ============================================================================
+
+/// Iterator over a reference to the [`Ok`] value of a [`Result0`].
+/// Created by [`Result0::iter`] or by iterating over `&Result0<T, E>`.
+pub struct Iter<'a, T> {
+    // Handed out on the first `next`, then left as `None`
+    inner: Option<&'a T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.inner.take()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.inner.is_some() as usize;
+        (n, Some(n))
+    }
+}
+
+/// Iterator over a mutable reference to the [`Ok`] value of a [`Result0`].
+/// Created by [`Result0::iter_mut`] or by iterating over `&mut Result0<T, E>`.
+pub struct IterMut<'a, T> {
+    inner: Option<&'a mut T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        self.inner.take()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.inner.is_some() as usize;
+        (n, Some(n))
+    }
+}
+
+/// Iterator that moves the [`Ok`] value out of a [`Result0`].
+/// Created by iterating over a `Result0<T, E>` by value.
+pub struct IntoIter<T> {
+    inner: Option<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.inner.take()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.inner.is_some() as usize;
+        (n, Some(n))
+    }
+}
+
+/// Iterating over a [`Result0`] by value yields the [`Ok`] value, if any.
+/// ```
+/// use rustlib::result::{Result0, Ok, Err};
+/// let words: Vec<String> = Ok::<String, i32>(String::from("hi")).into_iter().collect();
+/// assert_eq!(words, vec!["hi"]);
+/// ```
+impl<T, E> IntoIterator for Result0<T, E> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { inner: self.ok() }
+    }
+}
+
+/// Iterating over `&Result0<T, E>` borrows the [`Ok`] value, if any.
+/// ```
+/// use rustlib::result::{Result0, Ok, Err};
+/// let x: Result0<i32, &str> = Ok(3);
+/// for v in &x {
+///     assert_eq!(*v, 3);
+/// }
+/// assert_eq!(x, Ok(3));
+/// ```
+impl<'a, T, E> IntoIterator for &'a Result0<T, E> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+/// Iterating over `&mut Result0<T, E>` gives mutable access to the [`Ok`] value, if any.
+/// ```
+/// use rustlib::result::{Result0, Ok, Err};
+/// let mut x: Result0<i32, &str> = Ok(3);
+/// for v in &mut x {
+///     *v *= 2;
+/// }
+/// assert_eq!(x, Ok(6));
+/// ```
+impl<'a, T, E> IntoIterator for &'a mut Result0<T, E> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
 impl<T, E: fmt::Debug> Result0<T, E> {
     /// Returns the contained [`Ok`] value, panicking if [`Err`].
     /// ```
@@ -221,6 +510,33 @@ impl<T, E: fmt::Debug> Result0<T, E> {
     }
 }
 
+impl<T: fmt::Debug, E> Result0<T, E> {
+    /// Returns the contained [`Err`] value, panicking if [`Ok`].
+    /// Handy in tests that expect a failure.
+    /// ```
+    /// use rustlib::result::{Result0, Ok, Err};
+    /// assert_eq!(Err::<i32, &str>("bad input").unwrap_err(), "bad input");
+    /// ```
+    pub fn unwrap_err(self) -> E {
+        match self {
+            Ok(val) => panic!("called unwrap_err on Ok: {:?}", val),
+            Err(e) => e,
+        }
+    }
+
+    /// Returns the contained [`Err`] value, panicking with a custom message if [`Ok`].
+    /// ```
+    /// use rustlib::result::{Result0, Ok, Err};
+    /// assert_eq!(Err::<i32, &str>("bad input").expect_err("should fail"), "bad input");
+    /// ```
+    pub fn expect_err(self, msg: &str) -> E {
+        match self {
+            Ok(val) => panic!("{}: {:?}", msg, val),
+            Err(e) => e,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -280,6 +596,32 @@ mod tests {
         err.expect("custom message");
     }
 
+    #[test]
+    fn test_unwrap_err() {
+        let err: Result0<i32, &str> = Err("error");
+        assert_eq!(err.unwrap_err(), "error");
+    }
+
+    #[test]
+    #[should_panic(expected = "called unwrap_err on Ok: 42")]
+    fn test_unwrap_err_on_ok_panics() {
+        let ok: Result0<i32, &str> = Ok(42);
+        ok.unwrap_err();
+    }
+
+    #[test]
+    fn test_expect_err() {
+        let err: Result0<i32, &str> = Err("error");
+        assert_eq!(err.expect_err("should be err"), "error");
+    }
+
+    #[test]
+    #[should_panic(expected = "should have failed: 42")]
+    fn test_expect_err_on_ok_panics() {
+        let ok: Result0<i32, &str> = Ok(42);
+        ok.expect_err("should have failed");
+    }
+
     #[test]
     fn test_unwrap_or() {
         let ok: Result0<i32, &str> = Ok(42);
@@ -307,6 +649,29 @@ mod tests {
         assert_eq!(err.map(|x| x * 2), Err("error"));
     }
 
+    #[test]
+    fn test_inspect() {
+        let mut seen = Vec::new();
+        let ok: Result0<i32, String> = Ok(1);
+        assert_eq!(ok.inspect(|v| seen.push(*v)), Ok(1));
+        let err: Result0<i32, String> = Err(String::from("bad"));
+        assert_eq!(err.inspect(|v| seen.push(*v)), Err(String::from("bad")));
+        assert_eq!(seen, vec![1]);
+    }
+
+    #[test]
+    fn test_inspect_err() {
+        let mut seen = Vec::new();
+        let err: Result0<i32, String> = Err(String::from("bad"));
+        assert_eq!(
+            err.inspect_err(|e| seen.push(e.clone())),
+            Err(String::from("bad"))
+        );
+        let ok: Result0<i32, String> = Ok(1);
+        assert_eq!(ok.inspect_err(|e| seen.push(e.clone())), Ok(1));
+        assert_eq!(seen, vec!["bad"]);
+    }
+
     #[test]
     fn test_map_err() {
         let ok: Result0<i32, &str> = Ok(42);
@@ -340,6 +705,19 @@ mod tests {
         assert_eq!(ok, Ok(String::from("hello")));
     }
 
+    #[test]
+    fn test_as_mut() {
+        // e.g. results stored in a collection, updated without moving them out
+        let mut results: Vec<Result0<i32, String>> = vec![Ok(1), Err(String::from("e"))];
+        for result in results.iter_mut() {
+            match result.as_mut() {
+                Ok(x) => *x *= 10,
+                Err(e) => e.push('!'),
+            }
+        }
+        assert_eq!(results, vec![Ok(10), Err(String::from("e!"))]);
+    }
+
     #[test]
     fn test_or() {
         let ok1: Result0<i32, &str> = Ok(1);
@@ -379,6 +757,102 @@ mod tests {
         assert_eq!(err2.and(ok4), Err("error1"));
     }
 
+    #[test]
+    fn test_into_ok() {
+        let x = Result0::<i32, Infallible>::Ok(42);
+        assert_eq!(x.into_ok(), 42);
+
+        // A parser that can't fail
+        fn parse(s: &str) -> Result0<String, Infallible> {
+            Ok(s.to_uppercase())
+        }
+        assert_eq!(parse("abc").into_ok(), "ABC");
+    }
+
+    #[test]
+    fn test_into_err() {
+        let x = Result0::<Infallible, String>::Err(String::from("nope"));
+        assert_eq!(x.into_err(), "nope");
+    }
+
+    #[test]
+    fn test_copied() {
+        let (x, e) = (1, 'e');
+        let ok: Result0<&i32, &char> = Ok(&x);
+        let err: Result0<&i32, &char> = Err(&e);
+        assert_eq!(ok.copied(), Ok(1));
+        assert_eq!(err.copied(), Err('e'));
+    }
+
+    #[test]
+    fn test_cloned() {
+        let value = String::from("value");
+        let error = String::from("error");
+        let ok: Result0<&String, &String> = Ok(&value);
+        let err: Result0<&String, &String> = Err(&error);
+        assert_eq!(ok.cloned(), Ok(String::from("value")));
+        assert_eq!(err.cloned(), Err(String::from("error")));
+        // The originals are untouched
+        assert_eq!((value.as_str(), error.as_str()), ("value", "error"));
+
+        // Pairs naturally with as_ref
+        let owned: Result0<String, String> = Ok(String::from("owned"));
+        assert_eq!(owned.as_ref().cloned(), owned);
+    }
+
+    #[test]
+    fn test_transpose() {
+        let ok_some: Result0<Option0<i32>, &str> = Ok(Option0::Some(5));
+        assert_eq!(ok_some.transpose(), Option0::Some(Ok(5)));
+
+        let ok_none: Result0<Option0<i32>, &str> = Ok(Option0::None);
+        assert_eq!(ok_none.transpose(), Option0::None);
+
+        let err: Result0<Option0<i32>, &str> = Err("bad");
+        assert_eq!(err.transpose(), Option0::Some(Err("bad")));
+
+        // Transposing twice gets back where we started
+        let round_trip: Result0<Option0<i32>, &str> = Ok(Option0::Some(1));
+        assert_eq!(round_trip.clone().transpose().transpose(), round_trip);
+    }
+
+    #[test]
+    fn test_iter() {
+        let ok: Result0<i32, &str> = Ok(7);
+        let err: Result0<i32, &str> = Err("bad");
+        assert_eq!(ok.iter().collect::<Vec<_>>(), vec![&7]);
+        assert!(err.iter().collect::<Vec<_>>().is_empty());
+        assert_eq!(ok.iter().size_hint(), (1, Some(1)));
+        assert_eq!(err.iter().size_hint(), (0, Some(0)));
+    }
+
+    #[test]
+    fn test_iter_mut() {
+        let mut ok: Result0<String, i32> = Ok(String::from("a"));
+        for s in &mut ok {
+            s.push('b');
+        }
+        assert_eq!(ok, Ok(String::from("ab")));
+
+        let mut err: Result0<String, i32> = Err(1);
+        assert_eq!(err.iter_mut().count(), 0);
+        assert_eq!(err, Err(1));
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let ok: Result0<String, i32> = Ok(String::from("owned"));
+        assert_eq!(ok.into_iter().collect::<Vec<_>>(), vec!["owned"]);
+
+        let err: Result0<String, i32> = Err(1);
+        assert!(err.into_iter().collect::<Vec<_>>().is_empty());
+
+        // Flattening skips the errors
+        let results = vec![Ok(1), Err("bad"), Ok(3)];
+        let oks: Vec<i32> = results.into_iter().flatten().collect();
+        assert_eq!(oks, vec![1, 3]);
+    }
+
     #[test]
     fn test_flatten() {
         let ok_ok: Result0<Result0<i32, &str>, &str> = Ok(Ok(42));
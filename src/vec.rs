@@ -6,17 +6,39 @@
 //! extern crate rustlib;
 //! ```
 
+use crate::option::Option0;
+use crate::r#box::Box0;
+use crate::result::Result0;
 use std::alloc::{alloc, dealloc, realloc, Layout};
-use std::ops::{Deref, DerefMut, Index, IndexMut};
+use std::collections::LinkedList;
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Deref, DerefMut, Index, IndexMut, Mul, MulAssign};
 use std::ptr;
+use std::slice::SliceIndex;
 
 pub struct Vec0<T> {
     ptr: *mut T,
     len: usize,
     capacity: usize,
+    // Alignment of the buffer, at least `align_of::<T>()`
+    align: usize,
+}
+
+/// Why a fallible method like [`Vec0::try_reserve`] couldn't make room.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TryReserveError {
+    /// The requested capacity was too large to even ask for:
+    /// it overflows `usize`, or its layout exceeds `isize::MAX` bytes.
+    CapacityOverflow,
+    /// The allocator returned null for this layout.
+    AllocError { layout: Layout },
 }
 
 impl<T> Vec0<T> {
+    /// Zero-sized elements need no memory: a vector of them never allocates,
+    /// keeps a dangling pointer and has room for `usize::MAX` elements.
+    const IS_ZST: bool = std::mem::size_of::<T>() == 0;
+
     /// Creates an empty vector without allocating.
     /// ```
     /// use rustlib::vec::Vec0;
@@ -28,7 +50,8 @@ impl<T> Vec0<T> {
         Vec0 {
             ptr: std::ptr::NonNull::dangling().as_ptr(),
             len: 0,
-            capacity: 0,
+            capacity: if Self::IS_ZST { usize::MAX } else { 0 },
+            align: std::mem::align_of::<T>(),
         }
     }
 
@@ -40,11 +63,11 @@ impl<T> Vec0<T> {
     /// assert_eq!(v.capacity(), 10);
     /// ```
     pub fn with_capacity(capacity: usize) -> Vec0<T> {
-        if capacity == 0 {
+        if capacity == 0 || Self::IS_ZST {
             return Vec0::new();
         }
 
-        let layout = Layout::array::<T>(capacity).unwrap();
+        let layout = Layout::array::<T>(capacity).unwrap_or_else(|_| capacity_overflow());
         let ptr = unsafe { alloc(layout) as *mut T };
 
         if ptr.is_null() {
@@ -55,7 +78,58 @@ impl<T> Vec0<T> {
             ptr,
             len: 0,
             capacity,
+            align: std::mem::align_of::<T>(),
+        }
+    }
+
+    /// Creates an empty vector whose buffer is aligned to at least `align` bytes.
+    /// Useful for SIMD buffers or cache-line (64-byte) alignment.
+    /// The buffer keeps this alignment when it grows or shrinks.
+    /// ```
+    /// use rustlib::vec::Vec0;
+    /// let mut v: Vec0<u8> = Vec0::with_capacity_aligned(16, 64);
+    /// v.push(1);
+    /// assert_eq!(v.as_slice().as_ptr() as usize % 64, 0);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two.
+    pub fn with_capacity_aligned(capacity: usize, align: usize) -> Vec0<T> {
+        assert!(
+            align.is_power_of_two(),
+            "alignment must be a power of two: {}",
+            align
+        );
+
+        let mut vec = Vec0::new();
+        vec.align = std::cmp::max(std::mem::align_of::<T>(), align);
+
+        if capacity > 0 && !Self::IS_ZST {
+            let layout = vec.layout_for(capacity);
+            vec.ptr = unsafe { alloc(layout) as *mut T };
+
+            if vec.ptr.is_null() {
+                std::alloc::handle_alloc_error(layout);
+            }
+            vec.capacity = capacity;
         }
+
+        vec
+    }
+
+    /// Collects a range into a vector: `range_collect(start..end)` gives
+    /// `[start, start + 1, ..., end - 1]`.
+    /// ```
+    /// use rustlib::vec::Vec0;
+    /// let v = Vec0::range_collect(3..7);
+    /// assert_eq!(v.as_slice(), &[3, 4, 5, 6]);
+    /// ```
+    pub fn range_collect(range: std::ops::Range<T>) -> Vec0<T>
+    where
+        std::ops::Range<T>: Iterator<Item = T>,
+    {
+        range.collect()
     }
 
     /// Returns the number of elements in the vector.
@@ -180,6 +254,105 @@ impl<T> Vec0<T> {
         }
     }
 
+    /// Removes and returns the element at position `index` in O(1),
+    /// by moving the last element into its place. Doesn't preserve order.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0!["a", "b", "c", "d"];
+    /// assert_eq!(v.swap_remove(1), "b");
+    /// assert_eq!(v.as_slice(), &["a", "d", "c"]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= len`.
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        if index >= self.len {
+            panic!("swap_remove index out of bounds: {} >= {}", index, self.len);
+        }
+
+        unsafe {
+            let value = ptr::read(self.ptr.add(index));
+            self.len -= 1;
+            // Fill the hole with the last element (a no-op copy if index was the last)
+            ptr::copy(self.ptr.add(self.len), self.ptr.add(index), 1);
+            value
+        }
+    }
+
+    /// Like [`insert`](Vec0::insert), but returns `Err(value)` instead of panicking
+    /// when `index > len`, handing the value back so it isn't lost.
+    /// ```
+    /// use rustlib::vec0;
+    /// use rustlib::result::{Ok, Err};
+    /// let mut v = vec0![1, 3];
+    /// assert_eq!(v.try_insert(1, 2), Ok(()));
+    /// assert_eq!(v.try_insert(9, 4), Err(4));
+    /// assert_eq!(v.as_slice(), &[1, 2, 3]);
+    /// ```
+    pub fn try_insert(&mut self, index: usize, value: T) -> Result0<(), T> {
+        if index > self.len {
+            return Result0::Err(value);
+        }
+        self.insert(index, value);
+        Result0::Ok(())
+    }
+
+    /// Like [`remove`](Vec0::remove), but returns `None` instead of panicking
+    /// when `index >= len`.
+    /// ```
+    /// use rustlib::vec0;
+    /// use rustlib::option::{Some, None};
+    /// let mut v = vec0![1, 2];
+    /// assert_eq!(v.try_remove(0), Some(1));
+    /// assert_eq!(v.try_remove(1), None);
+    /// ```
+    pub fn try_remove(&mut self, index: usize) -> Option0<T> {
+        if index >= self.len {
+            return Option0::None;
+        }
+        Option0::Some(self.remove(index))
+    }
+
+    /// Removes the first element equal to `item` and returns it,
+    /// or returns [`None`] if there is no such element.
+    /// Later elements shift left, like [`remove`](Vec0::remove).
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0![1, 2, 3, 2];
+    /// assert_eq!(v.remove_item(&2), Some(2));
+    /// assert_eq!(v.as_slice(), &[1, 3, 2]);
+    /// assert_eq!(v.remove_item(&5), None);
+    /// ```
+    pub fn remove_item<Q: ?Sized>(&mut self, item: &Q) -> Option<T>
+    where
+        T: PartialEq<Q>,
+    {
+        let index = self.iter().position(|x| x == item)?;
+        Some(self.remove(index))
+    }
+
+    /// Swaps the elements at positions `i` and `j`.
+    /// The same as the slice's `swap`, with a panic message naming the bad index.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0!["a", "b", "c"];
+    /// v.swap_elements(0, 2);
+    /// assert_eq!(v.as_slice(), &["c", "b", "a"]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` or `j` is out of bounds.
+    pub fn swap_elements(&mut self, i: usize, j: usize) {
+        for index in [i, j] {
+            if index >= self.len {
+                panic!("swap index out of bounds: {} >= {}", index, self.len);
+            }
+        }
+        self.as_mut_slice().swap(i, j);
+    }
+
     /// Clears the vector, removing all elements. Capacity remains unchanged.
     /// ```
     /// use rustlib::vec::Vec0;
@@ -197,6 +370,123 @@ impl<T> Vec0<T> {
         }
     }
 
+    /// Shortens the vector to `len` elements, dropping the rest.
+    /// Does nothing if `len` is already at least the current length.
+    /// Capacity remains unchanged.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0![1, 2, 3, 4];
+    /// v.truncate(2);
+    /// assert_eq!(v.as_slice(), &[1, 2]);
+    /// assert_eq!(v.capacity(), 4);
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        let tail = std::ptr::slice_from_raw_parts_mut(unsafe { self.ptr.add(len) }, self.len - len);
+        // Shorten first, so a panicking destructor can't lead to a double drop
+        self.len = len;
+        unsafe {
+            ptr::drop_in_place(tail);
+        }
+    }
+
+    /// Resizes the vector to `new_len`, truncating or appending clones of `value`.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0!["a"];
+    /// v.resize(3, "b");
+    /// assert_eq!(v.as_slice(), &["a", "b", "b"]);
+    /// v.resize(1, "c");
+    /// assert_eq!(v.as_slice(), &["a"]);
+    /// ```
+    pub fn resize(&mut self, new_len: usize, value: T)
+    where
+        T: Clone,
+    {
+        if new_len <= self.len {
+            self.truncate(new_len);
+        } else {
+            let additional = new_len - self.len;
+            // Clone for all new slots but the last, which gets `value` itself
+            self.extend_with(additional - 1, || value.clone());
+            self.push(value);
+        }
+    }
+
+    /// Resizes the vector to `new_len`, truncating or appending values returned by `f`.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0![1];
+    /// let mut next = 1;
+    /// v.resize_with(4, || {
+    ///     next *= 2;
+    ///     next
+    /// });
+    /// assert_eq!(v.as_slice(), &[1, 2, 4, 8]);
+    /// ```
+    pub fn resize_with<F: FnMut() -> T>(&mut self, new_len: usize, f: F) {
+        if new_len <= self.len {
+            self.truncate(new_len);
+        } else {
+            self.extend_with(new_len - self.len, f);
+        }
+    }
+
+    /// Splits the vector in two at `at`. Returns a newly allocated vector with
+    /// the elements `[at, len)`, leaving `[0, at)` in `self`.
+    /// The capacity of `self` is unchanged.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0![1, 2, 3, 4];
+    /// let tail = v.split_off(1);
+    /// assert_eq!(v.as_slice(), &[1]);
+    /// assert_eq!(tail.as_slice(), &[2, 3, 4]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    pub fn split_off(&mut self, at: usize) -> Vec0<T> {
+        if at > self.len {
+            panic!("split_off index out of bounds: {} > {}", at, self.len);
+        }
+
+        let tail_len = self.len - at;
+        let mut tail = Vec0::with_capacity_aligned(tail_len, self.align);
+        unsafe {
+            // Move the tail over bit for bit; `self` forgets it by shrinking `len`
+            ptr::copy_nonoverlapping(self.ptr.add(at), tail.ptr, tail_len);
+        }
+        self.len = at;
+        tail.len = tail_len;
+        tail
+    }
+
+    /// Moves all elements of `other` to the end of `self`, leaving `other` empty.
+    /// `other` keeps its capacity, so it can be refilled without reallocating.
+    /// Unlike [`extend_from_slice`](Vec0::extend_from_slice), this moves the
+    /// elements and doesn't need `T: Clone`.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut a = vec0![String::from("a")];
+    /// let mut b = vec0![String::from("b"), String::from("c")];
+    /// a.append(&mut b);
+    /// assert_eq!(a.as_slice(), &["a", "b", "c"]);
+    /// assert!(b.is_empty());
+    /// ```
+    pub fn append(&mut self, other: &mut Vec0<T>) {
+        let count = other.len;
+        self.grow_for(count);
+        unsafe {
+            ptr::copy_nonoverlapping(other.ptr, self.ptr.add(self.len), count);
+        }
+        // The elements now belong to `self`
+        other.len = 0;
+        self.len += count;
+    }
+
     /// Shrinks the capacity to match the length.
     /// ```
     /// use rustlib::vec::Vec0;
@@ -206,15 +496,14 @@ impl<T> Vec0<T> {
     /// assert_eq!(v.capacity(), 1);
     /// ```
     pub fn shrink_to_fit(&mut self) {
-        if self.capacity == self.len {
+        if self.capacity == self.len || Self::IS_ZST {
             return;
         }
 
         if self.len == 0 {
             if self.capacity > 0 {
                 unsafe {
-                    let layout = Layout::array::<T>(self.capacity).unwrap();
-                    dealloc(self.ptr as *mut u8, layout);
+                    dealloc(self.ptr as *mut u8, self.layout_for(self.capacity));
                 }
             }
             self.ptr = std::ptr::NonNull::dangling().as_ptr();
@@ -222,11 +511,10 @@ impl<T> Vec0<T> {
             return;
         }
 
-        let new_layout = Layout::array::<T>(self.len).unwrap();
-        let old_layout = Layout::array::<T>(self.capacity).unwrap();
+        let new_layout = self.layout_for(self.len);
+        let old_layout = self.layout_for(self.capacity);
 
-        let new_ptr =
-            unsafe { realloc(self.ptr as *mut u8, old_layout, new_layout.size()) as *mut T };
+        let new_ptr = unsafe { self.reallocate(old_layout, new_layout) };
 
         if new_ptr.is_null() {
             std::alloc::handle_alloc_error(new_layout);
@@ -236,6 +524,29 @@ impl<T> Vec0<T> {
         self.capacity = self.len;
     }
 
+    /// Converts the vector into a [`Box0<[T]>`](Box0), dropping any spare capacity.
+    /// The buffer is handed over as is: the elements aren't moved or copied.
+    /// ```
+    /// use rustlib::vec0;
+    /// let v = vec0![1, 2, 3];
+    /// let boxed = v.into_boxed_slice();
+    /// assert_eq!(&*boxed, &[1, 2, 3]);
+    /// ```
+    pub fn into_boxed_slice(mut self) -> Box0<[T]> {
+        if self.align != std::mem::align_of::<T>() {
+            // Box0 frees with `T`'s own alignment, so move to a buffer that has it
+            let mut plain = Vec0::with_capacity(self.len);
+            plain.append(&mut self);
+            return plain.into_boxed_slice();
+        }
+
+        // Box0 frees exactly `len` elements' worth of memory
+        self.shrink_to_fit();
+        let vec = std::mem::ManuallyDrop::new(self);
+        let slice = ptr::slice_from_raw_parts_mut(vec.ptr, vec.len);
+        unsafe { Box0::from_raw(slice) }
+    }
+
     /// Returns a reference to the elements as a slice.
     /// ```
     /// use rustlib::vec::Vec0;
@@ -261,448 +572,3713 @@ impl<T> Vec0<T> {
         unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) }
     }
 
-    fn grow_if_needed(&mut self) {
-        if self.len == self.capacity {
-            self.grow();
+    /// Returns the unused capacity `[len, capacity)` as uninitialized slots.
+    /// Write into them, then commit the written prefix with [`set_len`](Vec0::set_len).
+    /// ```
+    /// use rustlib::vec::Vec0;
+    /// let mut v: Vec0<u8> = Vec0::with_capacity(4);
+    /// let spare = v.spare_capacity_mut();
+    /// spare[0].write(7);
+    /// spare[1].write(8);
+    /// unsafe { v.set_len(2) };
+    /// assert_eq!(v.as_slice(), &[7, 8]);
+    /// ```
+    pub fn spare_capacity_mut(&mut self) -> &mut [std::mem::MaybeUninit<T>] {
+        unsafe {
+            std::slice::from_raw_parts_mut(
+                self.ptr.add(self.len) as *mut std::mem::MaybeUninit<T>,
+                self.capacity - self.len,
+            )
         }
     }
 
-    fn grow(&mut self) {
-        let new_capacity = if self.capacity == 0 {
-            1
-        } else {
-            self.capacity * 2
-        };
-
-        let new_layout = Layout::array::<T>(new_capacity).unwrap();
+    /// Sets the length without touching the elements: nothing is
+    /// initialized when growing, and nothing is dropped when shrinking.
+    ///
+    /// # Safety
+    ///
+    /// - `new_len` must be at most [`capacity`](Vec0::capacity).
+    /// - The elements `[old_len, new_len)` must be initialized, for example
+    ///   through [`spare_capacity_mut`](Vec0::spare_capacity_mut).
+    /// - When shrinking, the elements `[new_len, old_len)` are forgotten:
+    ///   they leak unless the caller drops or moves them out.
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        debug_assert!(new_len <= self.capacity);
+        self.len = new_len;
+    }
 
-        let new_ptr = if self.capacity == 0 {
-            unsafe { alloc(new_layout) as *mut T }
-        } else {
-            let old_layout = Layout::array::<T>(self.capacity).unwrap();
-            unsafe { realloc(self.ptr as *mut u8, old_layout, new_layout.size()) as *mut T }
-        };
+    /// Returns a reference to the element at `index`, or [`None`] if out of bounds.
+    /// The non-panicking counterpart of `v[index]`.
+    ///
+    /// Like the slice method, `index` can also be a range, giving a subslice.
+    /// ```
+    /// use rustlib::vec0;
+    /// let v = vec0![10, 20, 30];
+    /// assert_eq!(v.get(2), Some(&30));
+    /// assert_eq!(v.get(3), None);
+    /// assert_eq!(v.get(1..3), Some(&[20, 30][..]));
+    /// assert_eq!(v.get(..4), None);
+    /// ```
+    pub fn get<I: SliceIndex<[T]>>(&self, index: I) -> Option<&I::Output> {
+        self.as_slice().get(index)
+    }
 
-        if new_ptr.is_null() {
-            std::alloc::handle_alloc_error(new_layout);
-        }
+    /// Returns a mutable reference to the element at `index`, or [`None`] if out of bounds.
+    /// The non-panicking counterpart of `v[index] = ...`.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0![10, 20, 30];
+    /// if let Some(x) = v.get_mut(1) {
+    ///     *x += 1;
+    /// }
+    /// assert_eq!(v.get_mut(3), None);
+    /// assert_eq!(v.as_slice(), &[10, 21, 30]);
+    /// ```
+    pub fn get_mut<I: SliceIndex<[T]>>(&mut self, index: I) -> Option<&mut I::Output> {
+        self.as_mut_slice().get_mut(index)
+    }
 
-        self.ptr = new_ptr;
-        self.capacity = new_capacity;
+    /// Returns a reference to the element at position `n`, or [`None`] if out of bounds.
+    /// Same as [`get`](Vec0::get).
+    /// ```
+    /// use rustlib::vec0;
+    /// let v = vec0![10, 20, 30];
+    /// assert_eq!(v.nth(1), Some(&20));
+    /// assert_eq!(v.nth(3), None);
+    /// ```
+    pub fn nth(&self, n: usize) -> Option<&T> {
+        self.as_slice().get(n)
     }
-}
 
-impl<T> Default for Vec0<T> {
-    fn default() -> Self {
-        Self::new()
+    /// Returns a mutable reference to the element at position `n`, or [`None`] if out of bounds.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0![10, 20, 30];
+    /// if let Some(x) = v.nth_mut(0) {
+    ///     *x = 5;
+    /// }
+    /// assert_eq!(v.as_slice(), &[5, 20, 30]);
+    /// ```
+    pub fn nth_mut(&mut self, n: usize) -> Option<&mut T> {
+        self.as_mut_slice().get_mut(n)
     }
-}
 
-/// Indexing into [`Vec0`] returns a reference to the element.
-/// ```
-/// use rustlib::vec::Vec0;
-/// let mut v = Vec0::new();
-/// v.push(10);
-/// assert_eq!(v[0], 10);
-/// ```
-impl<T> Index<usize> for Vec0<T> {
-    type Output = T;
+    /// Splits the elements into `[0, mid)` and `[mid, len)`,
+    /// or returns [`None`] if `mid > len`.
+    /// The slice's `split_at`, reachable through deref, panics instead.
+    /// ```
+    /// use rustlib::vec0;
+    /// let v = vec0![1, 2, 3];
+    /// assert_eq!(v.split_at_checked(1), Some((&[1][..], &[2, 3][..])));
+    /// assert_eq!(v.split_at_checked(4), None);
+    /// ```
+    pub fn split_at_checked(&self, mid: usize) -> Option<(&[T], &[T])> {
+        if mid > self.len {
+            return None;
+        }
+        Some(self.as_slice().split_at(mid))
+    }
 
-    fn index(&self, index: usize) -> &T {
-        if index >= self.len {
-            panic!("index out of bounds: {} >= {}", index, self.len);
+    /// Mutable version of [`split_at_checked`](Vec0::split_at_checked).
+    /// Both halves can be modified at once, since they don't overlap.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0![1, 2, 3, 4];
+    /// let (left, right) = v.split_at_mut_checked(2).unwrap();
+    /// left[0] = right[1];
+    /// assert_eq!(v.as_slice(), &[4, 2, 3, 4]);
+    /// ```
+    pub fn split_at_mut_checked(&mut self, mid: usize) -> Option<(&mut [T], &mut [T])> {
+        if mid > self.len {
+            return None;
         }
-        unsafe { &*self.ptr.add(index) }
+        Some(self.as_mut_slice().split_at_mut(mid))
     }
-}
 
-/// Mutable indexing allows modifying elements.
-/// ```
-/// use rustlib::vec::Vec0;
-/// let mut v = Vec0::new();
-/// v.push(10);
-/// v[0] = 20;
-/// assert_eq!(v[0], 20);
-/// ```
-impl<T> IndexMut<usize> for Vec0<T> {
-    fn index_mut(&mut self, index: usize) -> &mut T {
-        if index >= self.len {
-            panic!("index out of bounds: {} >= {}", index, self.len);
+    /// Returns a mutable reference to the first element, or [`None`] if empty.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0![1, 2, 3];
+    /// *v.first_mut().unwrap() = 10;
+    /// assert_eq!(v.as_slice(), &[10, 2, 3]);
+    /// ```
+    pub fn first_mut(&mut self) -> Option<&mut T> {
+        self.as_mut_slice().first_mut()
+    }
+
+    /// Returns a mutable reference to the last element, or [`None`] if empty.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0![1, 2, 3];
+    /// *v.last_mut().unwrap() = 30;
+    /// assert_eq!(v.as_slice(), &[1, 2, 30]);
+    /// ```
+    pub fn last_mut(&mut self) -> Option<&mut T> {
+        self.as_mut_slice().last_mut()
+    }
+
+    /// Returns an iterator that yields mutable references to each element.
+    /// ```
+    /// use rustlib::vec::Vec0;
+    /// let mut v = Vec0::new();
+    /// v.push(1);
+    /// v.push(2);
+    /// for x in v.iter_mut() {
+    ///     *x *= 10;
+    /// }
+    /// assert_eq!(v.as_slice(), &[10, 20]);
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            ptr: self.ptr,
+            remaining: self.len,
+            _marker: PhantomData,
         }
-        unsafe { &mut *self.ptr.add(index) }
     }
-}
 
-/// Dropping a [`Vec0`] drops all elements and deallocates memory.
-/// ```
-/// use rustlib::vec::Vec0;
-/// {
-///     let mut v = Vec0::new();
-///     v.push(String::from("hello"));
-/// } // v dropped here, memory freed
-/// ```
-impl<T> Drop for Vec0<T> {
-    fn drop(&mut self) {
-        if self.capacity > 0 {
+    /// Appends a clone of every element of `src`, reserving the space once up front.
+    /// For `T: Copy`, [`extend_from_copy_slice`](Vec0::extend_from_copy_slice) does it with one `memcpy`.
+    ///
+    /// If a `clone` panics, the elements cloned so far stay in the vector.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0![String::from("a")];
+    /// v.extend_from_slice(&[String::from("b"), String::from("c")]);
+    /// assert_eq!(v.as_slice(), &["a", "b", "c"]);
+    /// ```
+    pub fn extend_from_slice(&mut self, src: &[T])
+    where
+        T: Clone,
+    {
+        self.reserve(src.len());
+        for value in src {
             unsafe {
-                ptr::drop_in_place(std::ptr::slice_from_raw_parts_mut(self.ptr, self.len));
-                let layout = Layout::array::<T>(self.capacity).unwrap();
-                dealloc(self.ptr as *mut u8, layout);
+                ptr::write(self.ptr.add(self.len), value.clone());
             }
+            // Counted one at a time, so a panicking clone leaves `len` accurate
+            self.len += 1;
         }
     }
-}
 
-/// Dereferencing a [`Vec0<T>`] yields a `&[T]` slice.
-/// ```
-/// use rustlib::vec::Vec0;
-/// let mut v = Vec0::new();
-/// v.push(1);
-/// v.push(2);
-/// let _iter = v.iter(); // Uses [T]::iter() via deref coercion
-/// ```
-impl<T> Deref for Vec0<T> {
-    type Target = [T];
+    /// Appends a copy of every element of `src` with one bulk `memcpy`,
+    /// instead of pushing them one at a time.
+    ///
+    /// Only offered for `T: Copy`: copying the bits *is* a valid copy of the
+    /// value, and there is no `clone` that could panic halfway through.
+    ///
+    /// Not named `copy_from_slice`: through `Deref` that would shadow the
+    /// slice method of the same name, which overwrites in place instead.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0![1, 2];
+    /// v.extend_from_copy_slice(&[3, 4, 5]);
+    /// assert_eq!(v.as_slice(), &[1, 2, 3, 4, 5]);
+    /// ```
+    pub fn extend_from_copy_slice(&mut self, src: &[T])
+    where
+        T: Copy,
+    {
+        self.grow_for(src.len());
+        unsafe {
+            // `src` can't overlap our spare capacity: we hold `&mut self`
+            ptr::copy_nonoverlapping(src.as_ptr(), self.ptr.add(self.len), src.len());
+        }
+        self.len += src.len();
+    }
 
-    fn deref(&self) -> &[T] {
-        self.as_slice()
+    /// Reserves room for at least `additional` more elements, so the next
+    /// `additional` pushes don't reallocate. May reserve more than asked,
+    /// doubling the capacity like `push` does, to keep repeated calls cheap.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0![1];
+    /// v.reserve(10);
+    /// assert!(v.capacity() >= 11);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len + additional` overflows `usize`.
+    pub fn reserve(&mut self, additional: usize) {
+        self.grow_for(additional);
     }
-}
 
-/// Mutable dereferencing yields a `&mut [T]` slice.
-/// ```
-/// use rustlib::vec::Vec0;
-/// let mut v = Vec0::new();
-/// v.push(3);
-/// v.push(1);
-/// v.push(2);
-/// v.sort(); // Uses [T]::sort() via deref coercion
-/// assert_eq!(v[0], 1);
-/// assert_eq!(v[1], 2);
-/// assert_eq!(v[2], 3);
-/// ```
-impl<T> DerefMut for Vec0<T> {
-    fn deref_mut(&mut self) -> &mut [T] {
-        self.as_mut_slice()
+    /// Reserves room for exactly `additional` more elements, without rounding up.
+    /// Prefer [`reserve`](Vec0::reserve) when more pushes are likely to follow.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0![1];
+    /// v.reserve_exact(10);
+    /// assert_eq!(v.capacity(), 11);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len + additional` overflows `usize`.
+    pub fn reserve_exact(&mut self, additional: usize) {
+        let required = self.required_capacity(additional);
+        if required > self.capacity {
+            self.grow_to(required);
+        }
     }
-}
 
-/// Cloning creates a new [`Vec0`] with deep-copied elements.
-/// ```
-/// use rustlib::vec::Vec0;
-/// let mut v1 = Vec0::new();
-/// v1.push(1);
-/// let v2 = v1.clone();
-/// assert_eq!(v1[0], 1);
-/// assert_eq!(v2[0], 1); // independent copy
-/// ```
-impl<T: Clone> Clone for Vec0<T> {
-    fn clone(&self) -> Vec0<T> {
-        let mut new_vec = Vec0::with_capacity(self.len);
-        for i in 0..self.len {
-            new_vec.push(self[i].clone());
+    /// Like [`reserve`](Vec0::reserve), but returns an error instead of
+    /// panicking or aborting when the memory can't be had.
+    /// On failure the vector is left unchanged.
+    /// ```
+    /// use rustlib::vec::{TryReserveError, Vec0};
+    /// use rustlib::result::{Ok, Err};
+    /// let mut v: Vec0<u64> = Vec0::new();
+    /// assert_eq!(v.try_reserve(10), Ok(()));
+    /// assert!(v.capacity() >= 10);
+    /// assert_eq!(v.try_reserve(usize::MAX), Err(TryReserveError::CapacityOverflow));
+    /// ```
+    pub fn try_reserve(&mut self, additional: usize) -> Result0<(), TryReserveError> {
+        match self.try_grow_for(additional) {
+            Ok(()) => Result0::Ok(()),
+            Err(e) => Result0::Err(e),
         }
-        new_vec
     }
-}
 
-/// Debug formatting shows the vector as a list.
-/// ```
-/// use rustlib::vec::Vec0;
-/// let mut v = Vec0::new();
-/// v.push(1);
-/// v.push(2);
-/// assert_eq!(format!("{:?}", v), "[1, 2]");
-/// ```
-impl<T: std::fmt::Debug> std::fmt::Debug for Vec0<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_list().entries(self.as_slice().iter()).finish()
+    /// Like [`reserve_exact`](Vec0::reserve_exact), but returns an error instead of
+    /// panicking or aborting when the memory can't be had.
+    /// On failure the vector is left unchanged.
+    /// ```
+    /// use rustlib::vec0;
+    /// use rustlib::vec::TryReserveError;
+    /// use rustlib::result::{Ok, Err};
+    /// let mut v = vec0![1u64];
+    /// assert_eq!(v.try_reserve_exact(4), Ok(()));
+    /// assert_eq!(v.capacity(), 5);
+    /// assert_eq!(
+    ///     v.try_reserve_exact(isize::MAX as usize),
+    ///     Err(TryReserveError::CapacityOverflow)
+    /// );
+    /// ```
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result0<(), TryReserveError> {
+        let result = match self.len.checked_add(additional) {
+            Some(required) if required > self.capacity => self.try_grow_to(required),
+            Some(_) => Ok(()),
+            None => Err(TryReserveError::CapacityOverflow),
+        };
+        match result {
+            Ok(()) => Result0::Ok(()),
+            Err(e) => Result0::Err(e),
+        }
+    }
+
+    /// Appends clones of the elements of `src`, reserving the space first.
+    /// If that allocation fails, returns the error and appends nothing,
+    /// where `extend` would abort the process.
+    /// ```
+    /// use rustlib::vec0;
+    /// use rustlib::result::Ok;
+    /// let mut v = vec0![String::from("a")];
+    /// assert_eq!(v.try_extend_from_slice(&[String::from("b")]), Ok(()));
+    /// assert_eq!(v.as_slice(), &["a", "b"]);
+    /// ```
+    pub fn try_extend_from_slice(&mut self, src: &[T]) -> Result0<(), TryReserveError>
+    where
+        T: Clone,
+    {
+        if let Err(e) = self.try_grow_for(src.len()) {
+            return Result0::Err(e);
+        }
+        // Capacity is already there, so these pushes never allocate
+        for value in src {
+            self.push(value.clone());
+        }
+        Result0::Ok(())
+    }
+
+    /// Appends `additional` elements, each produced by calling `f`.
+    /// Unlike `vec0![x; n]`, the values don't need to be `Clone`:
+    /// every element is built fresh. Space is reserved once up front.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0![0];
+    /// let mut next = 0;
+    /// v.extend_with(3, || {
+    ///     next += 10;
+    ///     next
+    /// });
+    /// assert_eq!(v.as_slice(), &[0, 10, 20, 30]);
+    /// ```
+    pub fn extend_with<F: FnMut() -> T>(&mut self, additional: usize, mut f: F) {
+        self.grow_for(additional);
+        for _ in 0..additional {
+            self.push(f());
+        }
+    }
+
+    /// Maps each element to an iterator and collects all of their items
+    /// into a new vector. Uses each iterator's `size_hint` to reserve space up front.
+    /// ```
+    /// use rustlib::vec0;
+    /// let v = vec0![1, 2, 3];
+    /// let repeated = v.flat_map(|x| std::iter::repeat(x).take(x));
+    /// assert_eq!(repeated.as_slice(), &[1, 2, 2, 3, 3, 3]);
+    /// ```
+    pub fn flat_map<U, I, F>(self, mut f: F) -> Vec0<U>
+    where
+        I: IntoIterator<Item = U>,
+        F: FnMut(T) -> I,
+    {
+        let mut result = Vec0::new();
+        for value in self {
+            let iter = f(value).into_iter();
+            // Lower bound is 0 when the iterator can't tell, then we just push
+            let (lower, _) = iter.size_hint();
+            result.grow_for(lower);
+            for item in iter {
+                result.push(item);
+            }
+        }
+        result
+    }
+
+    /// Consumes the vector, splitting it into two: elements for which `f`
+    /// returns `true` go in the first, the rest in the second.
+    /// Elements are moved, never cloned.
+    /// ```
+    /// use rustlib::vec0;
+    /// let (even, odd) = vec0![1, 2, 3, 4, 5].partition(|x| x % 2 == 0);
+    /// assert_eq!(even.as_slice(), &[2, 4]);
+    /// assert_eq!(odd.as_slice(), &[1, 3, 5]);
+    /// ```
+    pub fn partition<F: FnMut(&T) -> bool>(self, mut f: F) -> (Vec0<T>, Vec0<T>) {
+        let mut matching = Vec0::new();
+        let mut rest = Vec0::new();
+        for value in self {
+            if f(&value) {
+                matching.push(value);
+            } else {
+                rest.push(value);
+            }
+        }
+        (matching, rest)
+    }
+
+    /// Sorts the vector, then removes duplicates.
+    /// Afterwards every value appears exactly once, in ascending order.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0![3, 1, 3, 2, 1];
+    /// v.sort_dedup();
+    /// assert_eq!(v.as_slice(), &[1, 2, 3]);
+    /// ```
+    pub fn sort_dedup(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort();
+        self.dedup_by(|a, b| a == b);
+    }
+
+    /// Sorts the vector with `compare`, then removes elements that
+    /// `compare` considers equal to their predecessor.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0!["b", "A", "a", "B"];
+    /// v.sort_dedup_by(|a, b| a.to_lowercase().cmp(&b.to_lowercase()));
+    /// assert_eq!(v.len(), 2);
+    /// ```
+    pub fn sort_dedup_by<F: FnMut(&T, &T) -> std::cmp::Ordering>(&mut self, mut compare: F) {
+        self.sort_by(&mut compare);
+        self.dedup_by(|a, b| compare(a, b) == std::cmp::Ordering::Equal);
+    }
+
+    /// Returns a reference to the smallest element, or [`None`] if empty.
+    /// ```
+    /// use rustlib::vec0;
+    /// assert_eq!(vec0![3, 1, 2].min(), Some(&1));
+    /// ```
+    pub fn min(&self) -> Option<&T>
+    where
+        T: Ord,
+    {
+        self.as_slice().iter().min()
+    }
+
+    /// Returns a reference to the largest element, or [`None`] if empty.
+    /// ```
+    /// use rustlib::vec0;
+    /// assert_eq!(vec0![3, 1, 2].max(), Some(&3));
+    /// ```
+    pub fn max(&self) -> Option<&T>
+    where
+        T: Ord,
+    {
+        self.as_slice().iter().max()
+    }
+
+    /// Returns the element with the smallest key computed by `f`.
+    /// If several elements are equally minimal, the first is returned.
+    /// ```
+    /// use rustlib::vec0;
+    /// let v = vec0!["ccc", "a", "bb"];
+    /// assert_eq!(v.min_by_key(|s| s.len()), Some(&"a"));
+    /// ```
+    pub fn min_by_key<K: Ord, F: FnMut(&T) -> K>(&self, mut f: F) -> Option<&T> {
+        self.as_slice().iter().min_by_key(|x| f(x))
+    }
+
+    /// Returns the element with the largest key computed by `f`.
+    /// If several elements are equally maximal, the last is returned.
+    /// ```
+    /// use rustlib::vec0;
+    /// let v = vec0!["ccc", "a", "bb"];
+    /// assert_eq!(v.max_by_key(|s| s.len()), Some(&"ccc"));
+    /// ```
+    pub fn max_by_key<K: Ord, F: FnMut(&T) -> K>(&self, mut f: F) -> Option<&T> {
+        self.as_slice().iter().max_by_key(|x| f(x))
+    }
+
+    /// Sums the elements without consuming the vector.
+    /// ```
+    /// use rustlib::vec0;
+    /// let v = vec0![1, 2, 3, 4];
+    /// assert_eq!(v.sum::<i32>(), 10);
+    /// ```
+    pub fn sum<S>(&self) -> S
+    where
+        S: std::iter::Sum<T>,
+        T: Copy,
+    {
+        self.iter().copied().sum()
+    }
+
+    /// Multiplies the elements without consuming the vector.
+    /// ```
+    /// use rustlib::vec0;
+    /// let v = vec0![1, 2, 3, 4];
+    /// assert_eq!(v.product::<i32>(), 24);
+    /// ```
+    pub fn product<P>(&self) -> P
+    where
+        P: std::iter::Product<T>,
+        T: Copy,
+    {
+        self.iter().copied().product()
+    }
+
+    /// Counts the elements for which `f` returns `true`.
+    /// ```
+    /// use rustlib::vec0;
+    /// let v = vec0![1, 2, 3, 4, 5];
+    /// assert_eq!(v.count_by(|x| x % 2 == 1), 3);
+    /// ```
+    pub fn count_by<F: FnMut(&T) -> bool>(&self, mut f: F) -> usize {
+        self.iter().filter(|x| f(x)).count()
+    }
+
+    /// Returns `true` if `f` holds for at least one element.
+    /// Stops at the first match; an empty vector gives `false`.
+    /// ```
+    /// use rustlib::vec0;
+    /// let v = vec0![1, 2, 3];
+    /// assert!(v.any(|x| *x > 2));
+    /// assert!(!v.any(|x| *x > 3));
+    /// ```
+    pub fn any<F: FnMut(&T) -> bool>(&self, f: F) -> bool {
+        self.iter().any(f)
+    }
+
+    /// Returns `true` if `f` holds for every element.
+    /// Stops at the first miss; an empty vector gives `true`.
+    /// ```
+    /// use rustlib::vec0;
+    /// let v = vec0![2, 4, 6];
+    /// assert!(v.all(|x| x % 2 == 0));
+    /// assert!(!v.all(|x| *x < 6));
+    /// ```
+    pub fn all<F: FnMut(&T) -> bool>(&self, f: F) -> bool {
+        self.iter().all(f)
+    }
+
+    /// Pops elements from the back while `predicate` holds and returns them
+    /// in their original order. Stops at the first element that fails,
+    /// leaving it in place.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut stack = vec0![1, 2, 3, 10, 11];
+    /// let big = stack.take_while_pop(|x| *x >= 10);
+    /// assert_eq!(big.as_slice(), &[10, 11]);
+    /// assert_eq!(stack.as_slice(), &[1, 2, 3]);
+    /// ```
+    pub fn take_while_pop<F: FnMut(&T) -> bool>(&mut self, mut predicate: F) -> Vec0<T> {
+        let mut taken = Vec0::new();
+        while let Some(value) = self.pop() {
+            if predicate(&value) {
+                taken.push(value);
+            } else {
+                self.push(value);
+                break;
+            }
+        }
+        // Popping reversed them
+        taken.reverse();
+        taken
+    }
+
+    /// Splits the vector into maximal runs where `same_group` holds for every
+    /// pair of neighbours. The runs are slices borrowing from this vector's buffer.
+    /// ```
+    /// use rustlib::vec0;
+    /// let v = vec0![1, 1, 2, 3, 3, 3];
+    /// let groups = v.group_by(|a, b| a == b);
+    /// assert_eq!(groups.as_slice(), &[&[1, 1][..], &[2], &[3, 3, 3]]);
+    /// ```
+    pub fn group_by<F: FnMut(&T, &T) -> bool>(&self, mut same_group: F) -> Vec0<&[T]> {
+        let slice = self.as_slice();
+        let mut groups = Vec0::new();
+        let mut start = 0;
+        for i in 1..slice.len() {
+            if !same_group(&slice[i - 1], &slice[i]) {
+                groups.push(&slice[start..i]);
+                start = i;
+            }
+        }
+        if start < slice.len() {
+            groups.push(&slice[start..]);
+        }
+        groups
+    }
+
+    /// Removes consecutive equal elements, keeping the first of each run.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0![1, 1, 2, 3, 3, 3, 1];
+    /// v.dedup();
+    /// assert_eq!(v.as_slice(), &[1, 2, 3, 1]);
+    /// ```
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b)
+    }
+
+    /// Removes consecutive elements for which `same_bucket(current, previous)`
+    /// returns `true`, keeping the first of each run.
+    ///
+    /// Works in place with a read and a write cursor. If `same_bucket` panics,
+    /// the guard closes the gap so no element is dropped twice or leaked.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0!["foo", "FOO", "bar", "Bar", "foo"];
+    /// v.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+    /// assert_eq!(v.as_slice(), &["foo", "bar", "foo"]);
+    /// ```
+    pub fn dedup_by<F: FnMut(&mut T, &mut T) -> bool>(&mut self, mut same_bucket: F) {
+        if self.len <= 1 {
+            return;
+        }
+
+        let len = self.len;
+        let mut guard = FillGapOnDrop {
+            vec: self,
+            read: 1,
+            write: 1,
+            len,
+        };
+
+        while guard.read < guard.len {
+            unsafe {
+                let current = guard.vec.ptr.add(guard.read);
+                let previous = guard.vec.ptr.add(guard.write - 1);
+                if same_bucket(&mut *current, &mut *previous) {
+                    guard.read += 1;
+                    ptr::drop_in_place(current);
+                } else {
+                    ptr::copy(current, guard.vec.ptr.add(guard.write), 1);
+                    guard.read += 1;
+                    guard.write += 1;
+                }
+            }
+        }
+        // guard drops here and sets len = write
+    }
+
+    /// Removes consecutive elements that map to the same key, keeping the
+    /// first of each run. Only *neighbouring* duplicates are removed:
+    /// sort first (or use [`sort_dedup`](Vec0::sort_dedup)) to remove them all.
+    ///
+    /// Works in place with two cursors. `read` scans every element, `write`
+    /// marks the end of the kept prefix. A new key is moved down to `write`,
+    /// a repeated key is dropped (✗) and leaves a gap that later moves fill:
+    ///
+    /// ```text
+    ///  key = x / 10       keys:  1    1    2    2    3
+    ///                         [ 11 | 12 | 25 | 21 | 30 ]    read = 1, write = 1
+    ///
+    ///  12: same key as 11     [ 11 | ✗  | 25 | 21 | 30 ]    read = 2, write = 1
+    ///  25: new key, move      [ 11 | 25 | ·  | 21 | 30 ]    read = 3, write = 2
+    ///  21: same key as 25     [ 11 | 25 | ·  | ✗  | 30 ]    read = 4, write = 2
+    ///  30: new key, move      [ 11 | 25 | 30 | ·  | ·  ]    read = 5, write = 3
+    ///
+    ///  len = write = 3        [ 11 | 25 | 30 ]
+    /// ```
+    ///
+    /// No allocation happens, and every element is either kept or dropped exactly once.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0![11, 12, 25, 21, 30, 14];
+    /// v.dedup_consecutive_by_key(|x| *x / 10);
+    /// assert_eq!(v.as_slice(), &[11, 25, 30, 14]);
+    /// ```
+    pub fn dedup_consecutive_by_key<K, F>(&mut self, mut key: F)
+    where
+        K: PartialEq,
+        F: FnMut(&mut T) -> K,
+    {
+        self.dedup_by(|current, previous| key(current) == key(previous))
+    }
+
+    /// Same as [`dedup_consecutive_by_key`](Vec0::dedup_consecutive_by_key),
+    /// under the name `std::vec::Vec` uses.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0![1, 1, 2];
+    /// v.dedup_by_key(|x| *x);
+    /// assert_eq!(v.as_slice(), &[1, 2]);
+    /// ```
+    pub fn dedup_by_key<K, F>(&mut self, key: F)
+    where
+        K: PartialEq,
+        F: FnMut(&mut T) -> K,
+    {
+        self.dedup_consecutive_by_key(key)
+    }
+
+    /// Keeps only the elements for which `f` returns `true`, in their original order.
+    /// Removed elements are dropped right away; capacity is unchanged.
+    ///
+    /// Same two-cursor technique as
+    /// [`dedup_consecutive_by_key`](Vec0::dedup_consecutive_by_key): kept elements
+    /// move down to `write`, rejected ones are dropped. If `f` panics, the guard
+    /// shifts the unvisited tail down over the gap, so every element is still
+    /// dropped exactly once.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0![1, 2, 3, 4, 5];
+    /// v.retain(|x| x % 2 == 1);
+    /// assert_eq!(v.as_slice(), &[1, 3, 5]);
+    /// ```
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        self.retain_mut(|x| f(x))
+    }
+
+    /// Like [`retain`](Vec0::retain), but `f` gets each element mutably,
+    /// so it can change the elements it keeps in the same pass.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0![String::from(" a "), String::from("  "), String::from("b ")];
+    /// v.retain_mut(|s| {
+    ///     *s = s.trim().to_string();
+    ///     !s.is_empty()
+    /// });
+    /// assert_eq!(v.as_slice(), &["a", "b"]);
+    /// ```
+    pub fn retain_mut<F: FnMut(&mut T) -> bool>(&mut self, mut f: F) {
+        let len = self.len;
+        let mut guard = FillGapOnDrop {
+            vec: self,
+            read: 0,
+            write: 0,
+            len,
+        };
+
+        while guard.read < guard.len {
+            unsafe {
+                let current = guard.vec.ptr.add(guard.read);
+                if f(&mut *current) {
+                    ptr::copy(current, guard.vec.ptr.add(guard.write), 1);
+                    guard.read += 1;
+                    guard.write += 1;
+                } else {
+                    // Advance first: if drop panics, this slot is already gone
+                    guard.read += 1;
+                    ptr::drop_in_place(current);
+                }
+            }
+        }
+        // guard drops here and sets len = write
+    }
+
+    /// Removes the elements in `range` and returns them as an iterator of owned values.
+    /// When the [`Drain`] is dropped, anything it didn't yield is dropped too, and
+    /// the elements after the range shift down to close the gap.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0![1, 2, 3, 4, 5];
+    /// let drained: Vec<_> = v.drain(1..3).collect();
+    /// assert_eq!(drained, vec![2, 3]);
+    /// assert_eq!(v.as_slice(), &[1, 4, 5]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range starts after it ends, or ends past `len`,
+    /// including a bound of `usize::MAX` that can't be made exclusive.
+    pub fn drain<R: std::ops::RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, T> {
+        use std::ops::Bound;
+
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start
+                .checked_add(1)
+                .unwrap_or_else(|| panic!("attempted to index slice from after maximum usize")),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end
+                .checked_add(1)
+                .unwrap_or_else(|| panic!("attempted to index slice up to maximum usize")),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.len,
+        };
+        if start > end {
+            panic!("drain start after end: {} > {}", start, end);
+        }
+        if end > self.len {
+            panic!("drain end out of bounds: {} > {}", end, self.len);
+        }
+
+        let tail_len = self.len - end;
+        // Until the Drain is dropped, the vector only owns `[0, start)`.
+        // If the Drain is leaked with `mem::forget`, the range and the tail
+        // leak with it, but nothing gets dropped twice.
+        self.len = start;
+        Drain {
+            vec: self,
+            index: start,
+            end,
+            tail_len,
+        }
+    }
+
+    /// Returns an iterator that removes and yields the elements for which
+    /// `f` returns `true`. The rest stay in the vector, in order.
+    ///
+    /// Elements are only visited as the iterator advances. Dropping it early
+    /// leaves the unvisited elements in place, like `std`'s `extract_if`.
+    /// ```
+    /// use rustlib::vec0;
+    /// let mut v = vec0![1, 2, 3, 4, 5, 6];
+    /// let evens: Vec<i32> = v.extract_if(|x| *x % 2 == 0).collect();
+    /// assert_eq!(evens, vec![2, 4, 6]);
+    /// assert_eq!(v.as_slice(), &[1, 3, 5]);
+    /// ```
+    pub fn extract_if<F: FnMut(&mut T) -> bool>(&mut self, f: F) -> ExtractIf<'_, T, F> {
+        let len = self.len;
+        // Until the ExtractIf is dropped, the vector owns nothing. If it is
+        // leaked with `mem::forget`, the elements leak with it, but nothing
+        // gets dropped twice.
+        self.len = 0;
+        ExtractIf {
+            guard: FillGapOnDrop {
+                vec: self,
+                read: 0,
+                write: 0,
+                len,
+            },
+            f,
+        }
+    }
+
+    fn grow_if_needed(&mut self) {
+        if self.len == self.capacity {
+            self.grow();
+        }
+    }
+
+    fn grow(&mut self) {
+        let new_capacity = if self.capacity == 0 {
+            1
+        } else {
+            self.capacity
+                .checked_mul(2)
+                .unwrap_or_else(|| capacity_overflow())
+        };
+
+        self.grow_to(new_capacity);
+    }
+
+    /// Makes room for at least `additional` more elements,
+    /// at least doubling the capacity so repeated calls stay amortized O(1).
+    fn grow_for(&mut self, additional: usize) {
+        let required = self.required_capacity(additional);
+        if required > self.capacity {
+            // Saturating: a doubled capacity too big for a layout fails in `layout_for`
+            self.grow_to(std::cmp::max(required, self.capacity.saturating_mul(2)));
+        }
+    }
+
+    /// `len + additional`, checked so it can't silently wrap around.
+    fn required_capacity(&self, additional: usize) -> usize {
+        self.len
+            .checked_add(additional)
+            .unwrap_or_else(|| panic!("capacity overflow: {} + {}", self.len, additional))
+    }
+
+    fn grow_to(&mut self, new_capacity: usize) {
+        let new_layout = self.layout_for(new_capacity);
+        let new_ptr = self.allocate_grown(new_layout);
+
+        if new_ptr.is_null() {
+            std::alloc::handle_alloc_error(new_layout);
+        }
+
+        self.ptr = new_ptr;
+        self.capacity = new_capacity;
+    }
+
+    /// Like [`grow_for`](Vec0::grow_for), but reports failure instead of aborting:
+    /// both a capacity too large to describe and an allocator returning null.
+    fn try_grow_for(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let required = self
+            .len
+            .checked_add(additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        if required <= self.capacity {
+            return Ok(());
+        }
+
+        self.try_grow_to(std::cmp::max(required, self.capacity.saturating_mul(2)))
+    }
+
+    /// Like [`grow_to`](Vec0::grow_to), but reports failure instead of aborting.
+    fn try_grow_to(&mut self, new_capacity: usize) -> Result<(), TryReserveError> {
+        let new_layout = Layout::array::<T>(new_capacity)
+            .and_then(|layout| layout.align_to(self.align))
+            .map_err(|_| TryReserveError::CapacityOverflow)?;
+        let new_ptr = self.allocate_grown(new_layout);
+
+        if new_ptr.is_null() {
+            return Err(TryReserveError::AllocError { layout: new_layout });
+        }
+
+        self.ptr = new_ptr;
+        self.capacity = new_capacity;
+        Ok(())
+    }
+
+    /// Returns a buffer for `new_layout` holding the current elements: a fresh
+    /// allocation if we had none, otherwise the old buffer moved or resized.
+    /// Null on failure, in which case the old buffer is untouched.
+    fn allocate_grown(&self, new_layout: Layout) -> *mut T {
+        if self.capacity == 0 {
+            unsafe { alloc(new_layout) as *mut T }
+        } else {
+            let old_layout = self.layout_for(self.capacity);
+            unsafe { self.reallocate(old_layout, new_layout) }
+        }
+    }
+
+    /// Layout of a buffer holding `capacity` elements with this vector's alignment.
+    fn layout_for(&self, capacity: usize) -> Layout {
+        Layout::array::<T>(capacity)
+            .and_then(|layout| layout.align_to(self.align))
+            .unwrap_or_else(|_| capacity_overflow())
+    }
+
+    /// Moves the buffer from `old_layout` to `new_layout`, returning the new pointer
+    /// (null on allocation failure). The caller updates `ptr` and `capacity`.
+    ///
+    /// For over-aligned buffers we don't rely on `realloc` keeping the alignment:
+    /// allocate a fresh block, copy the elements over and free the old one.
+    unsafe fn reallocate(&self, old_layout: Layout, new_layout: Layout) -> *mut T {
+        if self.align == std::mem::align_of::<T>() {
+            return realloc(self.ptr as *mut u8, old_layout, new_layout.size()) as *mut T;
+        }
+
+        let new_ptr = alloc(new_layout) as *mut T;
+        if !new_ptr.is_null() {
+            ptr::copy_nonoverlapping(self.ptr, new_ptr, self.len);
+            dealloc(self.ptr as *mut u8, old_layout);
+        }
+        new_ptr
     }
 }
 
-// ============================================================================
-// IntoIterator implementation
-// ============================================================================
+/// Called when a requested capacity doesn't fit in a `Layout` (more than
+/// `isize::MAX` bytes) or can't even be computed without overflowing `usize`.
+/// An allocator that returns null goes to `handle_alloc_error` instead.
+fn capacity_overflow() -> ! {
+    panic!("capacity overflow")
+}
 
-/// Iterator that consumes a [`Vec0`] and yields owned elements.
-/// Created by calling [`Vec0::into_iter`].
-pub struct IntoIter<T> {
-    ptr: *mut T,
+/// Used by in-place filtering: elements `[0, write)` are kept, `[write, read)`
+/// are already dropped or moved, `[read, len)` haven't been visited yet.
+/// On drop (normal exit or panic) the unvisited tail is shifted down over
+/// the gap and the vector's length is fixed up.
+struct FillGapOnDrop<'a, T> {
+    vec: &'a mut Vec0<T>,
+    read: usize,
+    write: usize,
     len: usize,
-    capacity: usize,
-    index: usize,
 }
 
-/// Iterating over [`IntoIter`] yields owned elements.
-/// ```
-/// use rustlib::vec0;
-/// let v = vec0![1, 2, 3];
-/// let mut iter = v.into_iter();
-/// assert_eq!(iter.next(), Some(1));
-/// assert_eq!(iter.next(), Some(2));
-/// ```
-impl<T> Iterator for IntoIter<T> {
-    type Item = T;
+impl<T> Drop for FillGapOnDrop<'_, T> {
+    fn drop(&mut self) {
+        let remaining = self.len - self.read;
+        unsafe {
+            ptr::copy(
+                self.vec.ptr.add(self.read),
+                self.vec.ptr.add(self.write),
+                remaining,
+            );
+        }
+        self.vec.len = self.write + remaining;
+    }
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.index < self.len {
-            let value = unsafe { ptr::read(self.ptr.add(self.index)) };
-            self.index += 1;
-            Some(value)
-        } else {
-            None
+impl<A, B> Vec0<(A, B)> {
+    /// Splits a vector of pairs into a vector of first elements
+    /// and a vector of second elements.
+    /// ```
+    /// use rustlib::vec0;
+    /// let (nums, names) = vec0![(1, "one"), (2, "two")].unzip();
+    /// assert_eq!(nums.as_slice(), &[1, 2]);
+    /// assert_eq!(names.as_slice(), &["one", "two"]);
+    /// ```
+    pub fn unzip(self) -> (Vec0<A>, Vec0<B>) {
+        let mut firsts = Vec0::with_capacity(self.len);
+        let mut seconds = Vec0::with_capacity(self.len);
+        for (a, b) in self {
+            firsts.push(a);
+            seconds.push(b);
+        }
+        (firsts, seconds)
+    }
+}
+
+impl<T: Clone> Vec0<&[T]> {
+    /// Concatenates the borrowed slices into one owned vector, cloning each element.
+    /// The total length is computed first, so there is exactly one allocation.
+    /// ```
+    /// use rustlib::vec0;
+    /// let (a, b) = (["x".to_string()], ["y".to_string(), "z".to_string()]);
+    /// let parts = vec0![&a[..], &b[..]];
+    /// assert_eq!(parts.flatten_refs().as_slice(), &["x", "y", "z"]);
+    /// ```
+    pub fn flatten_refs(self) -> Vec0<T> {
+        let total = self.iter().map(|slice| slice.len()).sum();
+        let mut result = Vec0::with_capacity(total);
+        for slice in self.iter() {
+            result.extend(slice.iter().cloned());
+        }
+        result
+    }
+}
+
+impl<T: Copy> Vec0<&[T]> {
+    /// Like [`flatten_refs`](Vec0::flatten_refs), but copies each slice with one
+    /// `memcpy` via [`extend_from_copy_slice`](Vec0::extend_from_copy_slice).
+    ///
+    /// It has to be a separate method: choosing the `Copy` path inside
+    /// `flatten_refs` automatically would need specialization, which stable Rust lacks.
+    /// ```
+    /// use rustlib::vec0;
+    /// let parts = vec0![&[1, 2][..], &[], &[3]];
+    /// assert_eq!(parts.flatten_refs_copied().as_slice(), &[1, 2, 3]);
+    /// ```
+    pub fn flatten_refs_copied(self) -> Vec0<T> {
+        let total = self.iter().map(|slice| slice.len()).sum();
+        let mut result = Vec0::with_capacity(total);
+        for slice in self.iter() {
+            result.extend_from_copy_slice(slice);
         }
+        result
     }
+}
+
+impl<T> Default for Vec0<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Indexing into [`Vec0`] returns a reference to the element.
+/// ```
+/// use rustlib::vec::Vec0;
+/// let mut v = Vec0::new();
+/// v.push(10);
+/// assert_eq!(v[0], 10);
+/// ```
+impl<T> Index<usize> for Vec0<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        if index >= self.len {
+            panic!("index out of bounds: {} >= {}", index, self.len);
+        }
+        unsafe { &*self.ptr.add(index) }
+    }
+}
+
+/// Mutable indexing allows modifying elements.
+/// ```
+/// use rustlib::vec::Vec0;
+/// let mut v = Vec0::new();
+/// v.push(10);
+/// v[0] = 20;
+/// assert_eq!(v[0], 20);
+/// ```
+impl<T> IndexMut<usize> for Vec0<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        if index >= self.len {
+            panic!("index out of bounds: {} >= {}", index, self.len);
+        }
+        unsafe { &mut *self.ptr.add(index) }
+    }
+}
+
+/// Indexing [`Vec0`] with a range returns a slice of those elements.
+/// ```
+/// use rustlib::vec0;
+/// let mut v = vec0![1, 2, 3, 4, 5];
+/// assert_eq!(&v[1..3], &[2, 3]);
+/// assert_eq!(&v[..], &[1, 2, 3, 4, 5]);
+/// assert_eq!(&v[..2], &[1, 2]);
+/// assert_eq!(&v[3..], &[4, 5]);
+/// assert_eq!(&v[1..=3], &[2, 3, 4]);
+/// assert_eq!(&v[..=1], &[1, 2]);
+/// v[..2].fill(0);
+/// assert_eq!(v.as_slice(), &[0, 0, 3, 4, 5]);
+/// ```
+///
+/// Delegates to the slice, which panics with a message naming the bad bound,
+/// e.g. "range end index 5 out of range for slice of length 3".
+macro_rules! impl_range_index {
+    ($($range:ty),*) => {
+        $(
+            impl<T> Index<$range> for Vec0<T> {
+                type Output = [T];
+
+                fn index(&self, range: $range) -> &[T] {
+                    &self.as_slice()[range]
+                }
+            }
+
+            impl<T> IndexMut<$range> for Vec0<T> {
+                fn index_mut(&mut self, range: $range) -> &mut [T] {
+                    &mut self.as_mut_slice()[range]
+                }
+            }
+        )*
+    };
+}
+
+impl_range_index!(
+    std::ops::Range<usize>,
+    std::ops::RangeFull,
+    std::ops::RangeTo<usize>,
+    std::ops::RangeFrom<usize>,
+    std::ops::RangeInclusive<usize>,
+    std::ops::RangeToInclusive<usize>
+);
+
+/// Dropping a [`Vec0`] drops all elements and deallocates memory.
+/// ```
+/// use rustlib::vec::Vec0;
+/// {
+///     let mut v = Vec0::new();
+///     v.push(String::from("hello"));
+/// } // v dropped here, memory freed
+/// ```
+impl<T> Drop for Vec0<T> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(std::ptr::slice_from_raw_parts_mut(self.ptr, self.len));
+            if self.capacity > 0 && !Self::IS_ZST {
+                dealloc(self.ptr as *mut u8, self.layout_for(self.capacity));
+            }
+        }
+    }
+}
+
+/// Dereferencing a [`Vec0<T>`] yields a `&[T]` slice.
+/// ```
+/// use rustlib::vec::Vec0;
+/// let mut v = Vec0::new();
+/// v.push(1);
+/// v.push(2);
+/// let _iter = v.iter(); // Uses [T]::iter() via deref coercion
+/// ```
+impl<T> Deref for Vec0<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+/// Mutable dereferencing yields a `&mut [T]` slice.
+/// ```
+/// use rustlib::vec::Vec0;
+/// let mut v = Vec0::new();
+/// v.push(3);
+/// v.push(1);
+/// v.push(2);
+/// v.sort(); // Uses [T]::sort() via deref coercion
+/// assert_eq!(v[0], 1);
+/// assert_eq!(v[1], 2);
+/// assert_eq!(v[2], 3);
+/// ```
+impl<T> DerefMut for Vec0<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+/// Cloning creates a new [`Vec0`] with deep-copied elements.
+/// ```
+/// use rustlib::vec::Vec0;
+/// let mut v1 = Vec0::new();
+/// v1.push(1);
+/// let v2 = v1.clone();
+/// assert_eq!(v1[0], 1);
+/// assert_eq!(v2[0], 1); // independent copy
+/// ```
+impl<T: Clone> Clone for Vec0<T> {
+    fn clone(&self) -> Vec0<T> {
+        // Keep the buffer alignment, like `split_off` does
+        let mut new_vec = Vec0::with_capacity_aligned(self.len, self.align);
+        for i in 0..self.len {
+            new_vec.push(self[i].clone());
+        }
+        new_vec
+    }
+}
+
+/// Debug formatting shows the vector as a list.
+/// ```
+/// use rustlib::vec::Vec0;
+/// let mut v = Vec0::new();
+/// v.push(1);
+/// v.push(2);
+/// assert_eq!(format!("{:?}", v), "[1, 2]");
+/// ```
+impl<T: std::fmt::Debug> std::fmt::Debug for Vec0<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.as_slice().iter()).finish()
+    }
+}
+
+/// Two vectors are equal when they have the same elements in the same order.
+/// Capacity and alignment don't matter.
+/// ```
+/// use rustlib::vec0;
+/// use rustlib::vec::Vec0;
+/// let mut a: Vec0<i32> = Vec0::with_capacity(10);
+/// a.push(1);
+/// assert_eq!(a, vec0![1]);
+/// assert_ne!(a, vec0![1, 2]);
+/// ```
+impl<T: PartialEq> PartialEq for Vec0<T> {
+    fn eq(&self, other: &Vec0<T>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Eq> Eq for Vec0<T> {}
+
+/// Hashing a [`Vec0`] hashes its length, then each element in order,
+/// the same as hashing the slice. So equal vectors hash equally.
+/// ```
+/// use rustlib::vec0;
+/// use std::collections::HashSet;
+/// let mut set = HashSet::new();
+/// set.insert(vec0![1, 2]);
+/// assert!(set.contains(&vec0![1, 2]));
+/// assert!(!set.contains(&vec0![2, 1]));
+/// ```
+impl<T: std::hash::Hash> std::hash::Hash for Vec0<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+/// Extending a [`Vec0`] pushes every element yielded by the iterator.
+/// ```
+/// use rustlib::vec::Vec0;
+/// let mut v = Vec0::new();
+/// v.push(1);
+/// v.extend(2..=4);
+/// assert_eq!(v.as_slice(), &[1, 2, 3, 4]);
+/// ```
+impl<T> Extend<T> for Vec0<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+/// Collects an iterator into a [`Vec0`], reserving the iterator's
+/// `size_hint` lower bound up front.
+/// ```
+/// use rustlib::vec::Vec0;
+/// let v: Vec0<i32> = (1..=3).map(|x| x * x).collect();
+/// assert_eq!(v.as_slice(), &[1, 4, 9]);
+/// ```
+impl<T> FromIterator<T> for Vec0<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Vec0<T> {
+        let iter = iter.into_iter();
+        let (lower, _) = iter.size_hint();
+        let mut vec = Vec0::with_capacity(lower);
+        vec.extend(iter);
+        vec
+    }
+}
+
+/// Adding two [`Vec0`]s concatenates them, moving the elements of both.
+/// The left-hand buffer is reused, and nothing is cloned, so `T` needn't be `Clone`.
+/// ```
+/// use rustlib::vec0;
+/// let v = vec0![1, 2] + vec0![3, 4];
+/// assert_eq!(v.as_slice(), &[1, 2, 3, 4]);
+/// ```
+impl<T> Add for Vec0<T> {
+    type Output = Vec0<T>;
+
+    fn add(self, mut rhs: Vec0<T>) -> Vec0<T> {
+        let mut lhs = self;
+        lhs.append(&mut rhs);
+        lhs
+    }
+}
+
+/// Adding two borrowed [`Vec0`]s clones their elements into a new vector,
+/// leaving both operands usable.
+/// ```
+/// use rustlib::vec0;
+/// let a = vec0![1, 2];
+/// let b = vec0![3];
+/// let v = &a + &b;
+/// assert_eq!(v.as_slice(), &[1, 2, 3]);
+/// assert_eq!(a.len() + b.len(), 3);
+/// ```
+impl<T: Clone> Add<&Vec0<T>> for &Vec0<T> {
+    type Output = Vec0<T>;
+
+    fn add(self, rhs: &Vec0<T>) -> Vec0<T> {
+        let mut result = Vec0::with_capacity(self.len + rhs.len);
+        result.extend_from_slice(self);
+        result.extend_from_slice(rhs);
+        result
+    }
+}
+
+/// `+=` moves the right-hand side's elements onto the end.
+/// ```
+/// use rustlib::vec0;
+/// let mut v = vec0![1, 2];
+/// v += vec0![3];
+/// assert_eq!(v.as_slice(), &[1, 2, 3]);
+/// ```
+impl<T> AddAssign for Vec0<T> {
+    fn add_assign(&mut self, mut rhs: Vec0<T>) {
+        self.append(&mut rhs);
+    }
+}
+
+/// Multiplying by `n` repeats the elements `n` times, like Python's `[1, 2] * 3`.
+/// ```
+/// use rustlib::vec0;
+/// let v = vec0![1, 2] * 3;
+/// assert_eq!(v.as_slice(), &[1, 2, 1, 2, 1, 2]);
+/// ```
+impl<T: Clone> Mul<usize> for Vec0<T> {
+    type Output = Vec0<T>;
+
+    fn mul(self, n: usize) -> Vec0<T> {
+        let total = self
+            .len
+            .checked_mul(n)
+            .unwrap_or_else(|| capacity_overflow());
+        let mut result = Vec0::with_capacity(total);
+        for _ in 0..n {
+            result.extend(self.iter().cloned());
+        }
+        result
+    }
+}
+
+/// `*= n` repeats the elements in place; `*= 0` empties the vector.
+/// ```
+/// use rustlib::vec0;
+/// let mut v = vec0!["ab"];
+/// v *= 2;
+/// assert_eq!(v.as_slice(), &["ab", "ab"]);
+/// ```
+impl<T: Clone> MulAssign<usize> for Vec0<T> {
+    fn mul_assign(&mut self, n: usize) {
+        if n == 0 {
+            self.clear();
+            return;
+        }
+
+        let original_len = self.len;
+        let needed = original_len
+            .checked_mul(n)
+            .unwrap_or_else(|| capacity_overflow());
+        if needed > self.capacity {
+            self.grow_to(needed);
+        }
+        for _ in 1..n {
+            for i in 0..original_len {
+                let value = self[i].clone();
+                self.push(value);
+            }
+        }
+    }
+}
+
+/// Copies the elements of a borrowed [`Vec0`] into a std `Vec`.
+/// ```
+/// use rustlib::vec0;
+/// let v = vec0![1, 2, 3];
+/// let std_vec = Vec::from(&v);
+/// assert_eq!(std_vec, vec![1, 2, 3]);
+/// ```
+impl<T: Copy> From<&Vec0<T>> for Vec<T> {
+    fn from(v: &Vec0<T>) -> Vec<T> {
+        Vec::from(v.as_slice())
+    }
+}
+
+/// Copies the elements of a borrowed std `Vec` into a new [`Vec0`].
+/// ```
+/// use rustlib::vec::Vec0;
+/// let std_vec = vec![1, 2, 3];
+/// let v = Vec0::from(&std_vec);
+/// assert_eq!(v.as_slice(), &[1, 2, 3]);
+/// ```
+impl<T: Copy> From<&Vec<T>> for Vec0<T> {
+    fn from(v: &Vec<T>) -> Vec0<T> {
+        let mut result = Vec0::with_capacity(v.len());
+        result.extend(v.iter().copied());
+        result
+    }
+}
+
+/// Clones the elements of a slice into a new [`Vec0`].
+/// ```
+/// use rustlib::vec::Vec0;
+/// let words = [String::from("a"), String::from("b")];
+/// let v = Vec0::from(&words[..]);
+/// assert_eq!(v.as_slice(), &words);
+/// ```
+impl<T: Clone> From<&[T]> for Vec0<T> {
+    fn from(slice: &[T]) -> Vec0<T> {
+        let mut result = Vec0::with_capacity(slice.len());
+        result.extend_from_slice(slice);
+        result
+    }
+}
+
+/// Moves the elements of an array into a new [`Vec0`].
+/// ```
+/// use rustlib::vec::Vec0;
+/// let v: Vec0<i32> = [1, 2, 3].into();
+/// assert_eq!(v.as_slice(), &[1, 2, 3]);
+/// ```
+impl<T, const N: usize> From<[T; N]> for Vec0<T> {
+    fn from(array: [T; N]) -> Vec0<T> {
+        let mut result = Vec0::with_capacity(N);
+        result.extend(array);
+        result
+    }
+}
+
+/// Moves the elements of a std `Vec` into a new [`Vec0`], without cloning.
+/// The two use different allocations, so the elements are moved one by one.
+/// ```
+/// use rustlib::vec::Vec0;
+/// let v = Vec0::from(vec![String::from("a"), String::from("b")]);
+/// assert_eq!(v.as_slice(), &["a", "b"]);
+/// ```
+impl<T> From<Vec<T>> for Vec0<T> {
+    fn from(v: Vec<T>) -> Vec0<T> {
+        let mut result = Vec0::with_capacity(v.len());
+        result.extend(v);
+        result
+    }
+}
+
+/// Moves the elements of a [`Vec0`] into a std `Vec`, without cloning.
+/// ```
+/// use rustlib::vec0;
+/// let std_vec: Vec<String> = vec0![String::from("a")].into();
+/// assert_eq!(std_vec, vec!["a"]);
+/// ```
+impl<T> From<Vec0<T>> for Vec<T> {
+    fn from(v: Vec0<T>) -> Vec<T> {
+        // IntoIter reports its exact length, so `collect` allocates once
+        v.into_iter().collect()
+    }
+}
+
+/// Moves the elements of a [`Vec0`] into a std `LinkedList`, one push at a time.
+///
+/// The two memory models are very different. A `Vec0` keeps its elements side by
+/// side in one buffer; a `LinkedList` allocates a separate node per element, each
+/// holding the value plus `prev`/`next` pointers:
+///
+/// ```text
+///  Vec0:        [ 1 | 2 | 3 ]                 1 allocation, contiguous
+///
+///  LinkedList:  [1]  <->  [2]  <->  [3]        3 allocations, scattered
+/// ```
+///
+/// So this conversion performs `len` allocations.
+/// ```
+/// use std::collections::LinkedList;
+/// use rustlib::vec0;
+/// let list = LinkedList::from(vec0![1, 2, 3]);
+/// assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+/// ```
+impl<T> From<Vec0<T>> for LinkedList<T> {
+    fn from(v: Vec0<T>) -> LinkedList<T> {
+        let mut list = LinkedList::new();
+        for value in v {
+            list.push_back(value);
+        }
+        list
+    }
+}
+
+/// Moves the elements of a std `LinkedList` into a new [`Vec0`].
+///
+/// The list's nodes are scattered across the heap, so walking them means
+/// chasing a pointer per element. The result packs them into a single buffer,
+/// sized up front from the list's length, so indexing and iteration become
+/// cache-friendly.
+/// ```
+/// use std::collections::LinkedList;
+/// use rustlib::vec::Vec0;
+/// let list: LinkedList<i32> = (1..=3).collect();
+/// let v = Vec0::from(list);
+/// assert_eq!(v.as_slice(), &[1, 2, 3]);
+/// ```
+impl<T> From<LinkedList<T>> for Vec0<T> {
+    fn from(list: LinkedList<T>) -> Vec0<T> {
+        let mut result = Vec0::with_capacity(list.len());
+        for value in list {
+            result.push(value);
+        }
+        result
+    }
+}
+
+// ============================================================================
+// IntoIterator implementation
+// ============================================================================
+
+/// Iterator that consumes a [`Vec0`] and yields owned elements.
+/// Created by calling [`Vec0::into_iter`].
+pub struct IntoIter<T> {
+    ptr: *mut T,
+    len: usize,
+    capacity: usize,
+    align: usize,
+    index: usize,
+}
+
+impl<T> IntoIter<T> {
+    /// The elements not yet yielded. Those before `index` were moved out.
+    fn remaining(&self) -> &[T] {
+        unsafe { std::slice::from_raw_parts(self.ptr.add(self.index), self.len - self.index) }
+    }
+}
+
+/// Iterating over [`IntoIter`] yields owned elements.
+/// ```
+/// use rustlib::vec0;
+/// let v = vec0![1, 2, 3];
+/// let mut iter = v.into_iter();
+/// assert_eq!(iter.next(), Some(1));
+/// assert_eq!(iter.next(), Some(2));
+/// ```
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index < self.len {
+            let value = unsafe { ptr::read(self.ptr.add(self.index)) };
+            self.index += 1;
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+/// [`IntoIter`] can also be consumed from the back, so `rev()` works.
+/// ```
+/// use rustlib::vec0;
+/// let mut iter = vec0![1, 2, 3, 4].into_iter();
+/// assert_eq!(iter.next_back(), Some(4));
+/// assert_eq!(iter.next(), Some(1));
+/// assert_eq!(iter.rev().collect::<Vec<_>>(), vec![3, 2]);
+/// ```
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.index < self.len {
+            // Shrinking `len` hands the last element over to the caller
+            self.len -= 1;
+            Some(unsafe { ptr::read(self.ptr.add(self.len)) })
+        } else {
+            None
+        }
+    }
+}
+
+/// The number of elements left is always known exactly.
+/// ```
+/// use rustlib::vec0;
+/// let mut iter = vec0![1, 2, 3].into_iter();
+/// iter.next();
+/// assert_eq!(iter.len(), 2);
+/// ```
+impl<T> ExactSizeIterator for IntoIter<T> {
+    fn len(&self) -> usize {
+        self.len - self.index
+    }
+}
+
+/// Debug formatting shows the elements not yet consumed.
+/// ```
+/// use rustlib::vec0;
+/// let mut iter = vec0![1, 2, 3].into_iter();
+/// iter.next();
+/// assert_eq!(format!("{:?}", iter), "IntoIter([2, 3])");
+/// ```
+impl<T: std::fmt::Debug> std::fmt::Debug for IntoIter<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.remaining()).finish()
+    }
+}
+
+/// Cloning an [`IntoIter`] clones only the elements not yet consumed,
+/// into a fresh buffer just big enough for them.
+/// ```
+/// use rustlib::vec0;
+/// let mut iter = vec0![1, 2, 3].into_iter();
+/// iter.next();
+/// let replay = iter.clone();
+/// assert_eq!(iter.collect::<Vec<_>>(), vec![2, 3]);
+/// assert_eq!(replay.collect::<Vec<_>>(), vec![2, 3]);
+/// ```
+impl<T: Clone> Clone for IntoIter<T> {
+    fn clone(&self) -> Self {
+        // Slots before `index` were moved out already, so we can't clone
+        // the whole buffer: only `[index, len)` holds live values.
+        let remaining = self.remaining();
+        let mut vec = Vec0::with_capacity_aligned(remaining.len(), self.align);
+        vec.extend(remaining.iter().cloned());
+        vec.into_iter()
+    }
+}
+
+/// Dropping [`IntoIter`] drops remaining unconsumed elements and frees memory.
+/// ```
+/// use rustlib::vec0;
+/// let v = vec0![String::from("a"), String::from("b")];
+/// let mut iter = v.into_iter();
+/// assert_eq!(iter.next(), Some(String::from("a")));
+/// // iter dropped, "b" is dropped and memory freed
+/// ```
+impl<T> Drop for IntoIter<T> {
+    fn drop(&mut self) {
+        // Drop remaining elements that weren't consumed. Elements taken from
+        // the front are below `index`, those taken from the back are at or
+        // above `len`, so each is dropped exactly once.
+        while self.index < self.len {
+            unsafe {
+                ptr::drop_in_place(self.ptr.add(self.index));
+            }
+            self.index += 1;
+        }
+        // Deallocate memory
+        if self.capacity > 0 && !Vec0::<T>::IS_ZST {
+            unsafe {
+                let layout = Layout::array::<T>(self.capacity)
+                    .and_then(|layout| layout.align_to(self.align))
+                    .unwrap();
+                dealloc(self.ptr as *mut u8, layout);
+            }
+        }
+    }
+}
+
+/// Converting [`Vec0`] into an iterator yields owned elements.
+/// ```
+/// use rustlib::vec0;
+/// let v = vec0![1, 2, 3];
+/// let mut sum = 0;
+/// for val in v {
+///     sum += val; // Takes ownership of each element
+/// }
+/// assert_eq!(sum, 6);
+/// // v is consumed, can't be used anymore
+/// ```
+impl<T> IntoIterator for Vec0<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let iter = IntoIter {
+            ptr: self.ptr,
+            len: self.len,
+            capacity: self.capacity,
+            align: self.align,
+            index: 0,
+        };
+        // Prevent the original vec from dropping
+        std::mem::forget(self);
+        iter
+    }
+}
+
+/// Iterating over `&Vec0<T>` borrows each element, leaving the vector usable.
+/// ```
+/// use rustlib::vec0;
+/// let v = vec0![1, 2, 3];
+/// let mut sum = 0;
+/// for x in &v {
+///     sum += x;
+/// }
+/// assert_eq!(sum, 6);
+/// assert_eq!(v.len(), 3); // v was only borrowed
+/// ```
+impl<'a, T> IntoIterator for &'a Vec0<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_slice().iter()
+    }
+}
+
+/// Iterating over `&mut Vec0<T>` yields `&mut T`, so elements can be changed in place.
+/// ```
+/// use rustlib::vec0;
+/// let mut v = vec0![1, 2, 3];
+/// for x in &mut v {
+///     *x *= 2;
+/// }
+/// assert_eq!(v.as_slice(), &[2, 4, 6]);
+/// ```
+impl<'a, T> IntoIterator for &'a mut Vec0<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+// ============================================================================
+// Drain - removes a range, yielding owned elements
+// ============================================================================
+
+/// Iterator that moves a range of elements out of a [`Vec0`].
+/// Created by calling [`Vec0::drain`].
+///
+/// ```text
+///  drain(1..3) on [ a | b | c | d | e ]     vec.len = 1 while draining
+///                   ^   ^^^^^^^   ^^^^^
+///                 kept  yielded   tail, moved down on drop
+///
+///  after drop:    [ a | d | e ]             vec.len = 3
+/// ```
+pub struct Drain<'a, T> {
+    vec: &'a mut Vec0<T>,
+    // Next element to yield; `[index, end)` hasn't been yielded yet
+    index: usize,
+    end: usize,
+    // Elements after the drained range, starting at `end`
+    tail_len: usize,
+}
+
+/// Iterating over [`Drain`] yields the removed elements in order.
+/// ```
+/// use rustlib::vec0;
+/// let mut v = vec0![String::from("a"), String::from("b")];
+/// let mut drain = v.drain(..);
+/// assert_eq!(drain.next(), Some(String::from("a")));
+/// ```
+impl<T> Iterator for Drain<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.index == self.end {
+            return None;
+        }
+        let value = unsafe { ptr::read(self.vec.ptr.add(self.index)) };
+        self.index += 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+/// Debug formatting shows the drained elements not yet yielded.
+/// ```
+/// use rustlib::vec0;
+/// let mut v = vec0![1, 2, 3, 4];
+/// let mut drain = v.drain(1..);
+/// drain.next();
+/// assert_eq!(format!("{:?}", drain), "Drain([3, 4])");
+/// ```
+impl<T: std::fmt::Debug> std::fmt::Debug for Drain<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let remaining = unsafe {
+            std::slice::from_raw_parts(self.vec.ptr.add(self.index), self.end - self.index)
+        };
+        f.debug_tuple("Drain").field(&remaining).finish()
+    }
+}
+
+/// Dropping [`Drain`] drops the elements it didn't yield and closes the gap.
+/// ```
+/// use rustlib::vec0;
+/// let mut v = vec0![1, 2, 3, 4];
+/// v.drain(..2); // never iterated
+/// assert_eq!(v.as_slice(), &[3, 4]);
+/// ```
+impl<T> Drop for Drain<'_, T> {
+    fn drop(&mut self) {
+        unsafe {
+            let remaining = self.end - self.index;
+            // Mark them as gone before dropping, in case a drop panics
+            let unyielded = ptr::slice_from_raw_parts_mut(self.vec.ptr.add(self.index), remaining);
+            self.index = self.end;
+            ptr::drop_in_place(unyielded);
+
+            let start = self.vec.len;
+            ptr::copy(
+                self.vec.ptr.add(self.end),
+                self.vec.ptr.add(start),
+                self.tail_len,
+            );
+            self.vec.len = start + self.tail_len;
+        }
+    }
+}
+
+// ============================================================================
+// ExtractIf - removes matching elements, yielding them owned
+// ============================================================================
+
+/// Iterator that moves the elements matching a predicate out of a [`Vec0`].
+/// Created by calling [`Vec0::extract_if`].
+///
+/// Uses the same cursors as [`retain`](Vec0::retain): kept elements move down
+/// to `write`, matching ones are read out and yielded. Dropping it, early or
+/// through a panic in `f`, shifts the unvisited tail down over the gap.
+pub struct ExtractIf<'a, T, F: FnMut(&mut T) -> bool> {
+    guard: FillGapOnDrop<'a, T>,
+    f: F,
+}
+
+/// Iterating over [`ExtractIf`] yields the matching elements in order.
+/// ```
+/// use rustlib::vec0;
+/// let mut v = vec0![1, 2, 3, 4];
+/// let mut iter = v.extract_if(|x| *x > 2);
+/// assert_eq!(iter.next(), Some(3));
+/// ```
+impl<T, F: FnMut(&mut T) -> bool> Iterator for ExtractIf<'_, T, F> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let guard = &mut self.guard;
+        while guard.read < guard.len {
+            unsafe {
+                let current = guard.vec.ptr.add(guard.read);
+                if (self.f)(&mut *current) {
+                    guard.read += 1;
+                    return Some(ptr::read(current));
+                }
+                ptr::copy(current, guard.vec.ptr.add(guard.write), 1);
+                guard.read += 1;
+                guard.write += 1;
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.guard.len - self.guard.read))
+    }
+}
+
+/// Debug formatting shows the elements not yet visited.
+/// ```
+/// use rustlib::vec0;
+/// let mut v = vec0![1, 2, 3, 4];
+/// let mut iter = v.extract_if(|x| *x == 2);
+/// iter.next();
+/// assert_eq!(format!("{:?}", iter), "ExtractIf([3, 4])");
+/// ```
+impl<T: std::fmt::Debug, F: FnMut(&mut T) -> bool> std::fmt::Debug for ExtractIf<'_, T, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let guard = &self.guard;
+        let unvisited = unsafe {
+            std::slice::from_raw_parts(guard.vec.ptr.add(guard.read), guard.len - guard.read)
+        };
+        f.debug_tuple("ExtractIf").field(&unvisited).finish()
+    }
+}
+
+// ============================================================================
+// IterMut - mutable borrowing iterator
+// ============================================================================
+
+/// Iterator over mutable references to the elements of a [`Vec0`].
+/// Created by calling [`Vec0::iter_mut`].
+///
+/// Instead of an index, it keeps a pointer `ptr` and a count of the elements
+/// left. Each call to `next` hands out the element at `ptr` and moves `ptr`
+/// forward, while `next_back` hands out the last one and shrinks the count,
+/// so no two `&mut T` ever point to the same slot.
+pub struct IterMut<'a, T> {
+    ptr: *mut T,
+    // A count rather than an end pointer: for zero-sized `T`,
+    // `ptr.add(1)` doesn't move and an end pointer would equal `ptr`
+    remaining: usize,
+    // Ties the iterator to the `&'a mut Vec0<T>` borrow it came from
+    _marker: PhantomData<&'a mut T>,
+}
+
+/// Iterating over [`IterMut`] yields `&mut T` for each element in order.
+/// ```
+/// use rustlib::vec0;
+/// let mut v = vec0![1, 2, 3];
+/// let mut iter = v.iter_mut();
+/// *iter.next().unwrap() = 10;
+/// assert_eq!(iter.next(), Some(&mut 2));
+/// assert_eq!(v[0], 10);
+/// ```
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            None
+        } else {
+            let current = self.ptr;
+            self.ptr = unsafe { self.ptr.add(1) };
+            self.remaining -= 1;
+            Some(unsafe { &mut *current })
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+/// [`IterMut`] can also be consumed from the back, so `rev()` works.
+/// ```
+/// use rustlib::vec0;
+/// let mut v = vec0![1, 2, 3];
+/// for (i, x) in v.iter_mut().rev().enumerate() {
+///     *x += i * 10;
+/// }
+/// assert_eq!(v.as_slice(), &[21, 12, 3]);
+/// ```
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            None
+        } else {
+            // Shrinking the count hands the last element over to the caller
+            self.remaining -= 1;
+            Some(unsafe { &mut *self.ptr.add(self.remaining) })
+        }
+    }
+}
+
+/// The number of elements left is always known exactly.
+/// ```
+/// use rustlib::vec0;
+/// let mut v = vec0![1, 2, 3];
+/// let mut iter = v.iter_mut();
+/// iter.next();
+/// assert_eq!(iter.len(), 2);
+/// ```
+impl<T> ExactSizeIterator for IterMut<'_, T> {
+    fn len(&self) -> usize {
+        self.remaining
+    }
+}
+
+/// Debug formatting shows the elements not yet handed out.
+/// ```
+/// use rustlib::vec0;
+/// let mut v = vec0![1, 2, 3];
+/// let mut iter = v.iter_mut();
+/// iter.next();
+/// assert_eq!(format!("{:?}", iter), "IterMut([2, 3])");
+/// ```
+impl<T: std::fmt::Debug> std::fmt::Debug for IterMut<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Only the elements not yet handed out are read: no `&mut` points in there
+        let remaining = unsafe { std::slice::from_raw_parts(self.ptr, self.remaining) };
+        f.debug_tuple("IterMut").field(&remaining).finish()
+    }
+}
+
+// ============================================================================
+// vec! macro - syntactic sugar for creating vectors
+// ============================================================================
+
+/// Creates a [`Vec0`] containing the given elements.
+///
+/// ```
+/// use rustlib::vec0;
+/// use rustlib::vec::Vec0;
+/// // Empty vector
+/// let v: Vec0<i32> = vec0![];
+///
+/// // Vector with elements
+/// let v = vec0![1, 2, 3];
+///
+/// // Vector with n copies of an element
+/// let v = vec0![0; 5]; // [0, 0, 0, 0, 0]
+/// ```
+#[macro_export]
+macro_rules! vec0 {
+    () => {
+        $crate::Vec0::new()
+    };
+    ($elem:expr; $n:expr) => {{
+        let mut v = $crate::Vec0::with_capacity($n);
+        #[allow(clippy::reversed_empty_ranges)]
+        for _ in 0..$n {
+            v.push($elem.clone());
+        }
+        v
+    }};
+    ($($x:expr),+ $(,)?) => {{
+        let mut v = $crate::Vec0::new();
+        $(v.push($x);)*
+        v
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let vec: Vec0<i32> = Vec0::new();
+        assert_eq!(vec.len(), 0);
+        assert_eq!(vec.capacity(), 0);
+        assert!(vec.is_empty());
+    }
+
+    #[test]
+    fn test_with_capacity() {
+        let vec: Vec0<i32> = Vec0::with_capacity(10);
+        assert_eq!(vec.len(), 0);
+        assert_eq!(vec.capacity(), 10);
+    }
+
+    #[test]
+    fn test_push_and_pop() {
+        let mut vec = Vec0::new();
+        vec.push(1);
+        vec.push(2);
+        vec.push(3);
+
+        assert_eq!(vec.len(), 3);
+        assert_eq!(vec.pop(), Some(3));
+        assert_eq!(vec.pop(), Some(2));
+        assert_eq!(vec.pop(), Some(1));
+        assert_eq!(vec.pop(), None);
+    }
+
+    #[test]
+    fn test_growth() {
+        let mut vec = Vec0::new();
+        assert_eq!(vec.capacity(), 0);
+
+        vec.push(1);
+        assert_eq!(vec.capacity(), 1);
+
+        vec.push(2);
+        assert_eq!(vec.capacity(), 2);
+
+        vec.push(3);
+        assert_eq!(vec.capacity(), 4);
+    }
+
+    #[test]
+    fn test_with_capacity_aligned() {
+        let mut vec: Vec0<u8> = Vec0::with_capacity_aligned(4, 64);
+        assert_eq!(vec.capacity(), 4);
+
+        // Alignment survives growth and shrinking
+        for i in 0..100 {
+            vec.push(i);
+            assert_eq!(vec.as_slice().as_ptr() as usize % 64, 0);
+        }
+        while vec.len() > 10 {
+            vec.pop();
+        }
+        vec.shrink_to_fit();
+        assert_eq!(vec.as_slice().as_ptr() as usize % 64, 0);
+        assert_eq!(vec.as_slice(), &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        // So does cloning
+        let clone = vec.clone();
+        assert_eq!(clone.as_slice().as_ptr() as usize % 64, 0);
+        assert_eq!(clone.as_slice(), vec.as_slice());
+    }
+
+    #[test]
+    fn test_with_capacity_aligned_smaller_than_type() {
+        // Requesting less than align_of::<T>() keeps T's natural alignment
+        let vec: Vec0<u64> = Vec0::with_capacity_aligned(4, 1);
+        assert_eq!(
+            vec.as_slice().as_ptr() as usize % std::mem::align_of::<u64>(),
+            0
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "alignment must be a power of two")]
+    fn test_with_capacity_aligned_not_power_of_two() {
+        let _vec: Vec0<u8> = Vec0::with_capacity_aligned(4, 48);
+    }
+
+    #[test]
+    fn test_index() {
+        let mut vec = Vec0::new();
+        vec.push(10);
+        vec.push(20);
+        vec.push(30);
+
+        assert_eq!(vec[0], 10);
+        assert_eq!(vec[1], 20);
+        assert_eq!(vec[2], 30);
+    }
+
+    #[test]
+    fn test_index_mut() {
+        let mut vec = Vec0::new();
+        vec.push(10);
+        vec.push(20);
+
+        vec[0] = 100;
+        assert_eq!(vec[0], 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn test_index_out_of_bounds() {
+        let vec: Vec0<i32> = Vec0::new();
+        let _ = vec[0];
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut vec = Vec0::new();
+        vec.push(1);
+        vec.push(3);
+        vec.insert(1, 2);
+
+        assert_eq!(vec[0], 1);
+        assert_eq!(vec[1], 2);
+        assert_eq!(vec[2], 3);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut vec = Vec0::new();
+        vec.push(1);
+        vec.push(2);
+        vec.push(3);
+
+        assert_eq!(vec.remove(1), 2);
+        assert_eq!(vec.len(), 2);
+        assert_eq!(vec[0], 1);
+        assert_eq!(vec[1], 3);
+    }
+
+    #[test]
+    fn test_swap_remove() {
+        let mut vec = vec0![String::from("a"), String::from("b"), String::from("c")];
+
+        assert_eq!(vec.swap_remove(0), "a");
+        assert_eq!(vec.len(), 2);
+        assert_eq!(vec.as_slice(), &["c", "b"]); // last element moved into index 0
+
+        // Removing the last element needs no move
+        assert_eq!(vec.swap_remove(1), "b");
+        assert_eq!(vec.as_slice(), &["c"]);
+        assert_eq!(vec.swap_remove(0), "c");
+        assert!(vec.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "swap_remove index out of bounds: 2 >= 2")]
+    fn test_swap_remove_out_of_bounds() {
+        let mut vec = vec0![1, 2];
+        vec.swap_remove(2);
+    }
+
+    #[test]
+    fn test_try_insert_try_remove() {
+        let mut vec = vec0![String::from("a"), String::from("c")];
+        assert_eq!(vec.try_insert(1, String::from("b")), Result0::Ok(()));
+        assert_eq!(vec.try_insert(3, String::from("d")), Result0::Ok(()));
+        // Out of bounds: the value comes back instead of being dropped
+        assert_eq!(
+            vec.try_insert(9, String::from("z")),
+            Result0::Err(String::from("z"))
+        );
+        assert_eq!(vec.as_slice(), &["a", "b", "c", "d"]);
+
+        assert_eq!(vec.try_remove(0), Option0::Some(String::from("a")));
+        assert_eq!(vec.try_remove(3), Option0::None);
+        assert_eq!(vec.as_slice(), &["b", "c", "d"]);
+    }
+
+    #[test]
+    fn test_remove_item() {
+        let mut vec = vec0![String::from("a"), String::from("b"), String::from("a")];
+        // String: PartialEq<str>, so a &str can be used to find it
+        assert_eq!(vec.remove_item("a"), Some(String::from("a")));
+        assert_eq!(vec.as_slice(), &["b", "a"]);
+        assert_eq!(vec.remove_item("c"), None);
+        assert_eq!(vec.len(), 2);
+
+        let mut empty: Vec0<i32> = Vec0::new();
+        assert_eq!(empty.remove_item(&1), None);
+    }
+
+    #[test]
+    fn test_swap_elements() {
+        let mut vec = vec0![1, 2, 3];
+        vec.swap_elements(0, 2);
+        assert_eq!(vec.as_slice(), &[3, 2, 1]);
+        vec.swap_elements(1, 1);
+        assert_eq!(vec.as_slice(), &[3, 2, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "swap index out of bounds: 3 >= 3")]
+    fn test_swap_elements_out_of_bounds() {
+        let mut vec = vec0![1, 2, 3];
+        vec.swap_elements(0, 3);
+    }
+
+    #[test]
+    fn test_truncate() {
+        use std::sync::Arc;
+
+        let item = Arc::new(0);
+        let mut vec: Vec0<Arc<i32>> = (0..5).map(|_| Arc::clone(&item)).collect();
+        let capacity = vec.capacity();
+        assert_eq!(Arc::strong_count(&item), 6);
+
+        vec.truncate(2);
+        assert_eq!(vec.len(), 2);
+        assert_eq!(vec.capacity(), capacity);
+        assert_eq!(Arc::strong_count(&item), 3);
+
+        // Truncating to a longer length does nothing
+        vec.truncate(10);
+        assert_eq!(vec.len(), 2);
+        assert_eq!(Arc::strong_count(&item), 3);
+
+        vec.truncate(0);
+        assert!(vec.is_empty());
+        assert_eq!(Arc::strong_count(&item), 1);
+    }
+
+    #[test]
+    fn test_resize() {
+        use std::sync::Arc;
+
+        let item = Arc::new(0);
+        let mut vec: Vec0<Arc<i32>> = Vec0::new();
+
+        vec.resize(4, Arc::clone(&item));
+        assert_eq!(vec.len(), 4);
+        // Exactly one Arc per slot: `value` itself was moved into the last one
+        assert_eq!(Arc::strong_count(&item), 5);
+
+        vec.resize(1, Arc::clone(&item));
+        assert_eq!(vec.len(), 1);
+        // The unused `value` was dropped along with the truncated elements
+        assert_eq!(Arc::strong_count(&item), 2);
+
+        vec.resize(1, Arc::clone(&item));
+        assert_eq!(vec.len(), 1);
+        assert_eq!(Arc::strong_count(&item), 2);
+
+        drop(vec);
+        assert_eq!(Arc::strong_count(&item), 1);
+    }
+
+    #[test]
+    fn test_resize_with() {
+        use std::sync::Arc;
+
+        let item = Arc::new(0);
+        let mut calls = 0;
+        let mut vec: Vec0<Arc<i32>> = vec0![Arc::clone(&item)];
+
+        vec.resize_with(3, || {
+            calls += 1;
+            Arc::clone(&item)
+        });
+        assert_eq!(calls, 2);
+        assert_eq!(vec.len(), 3);
+        assert_eq!(Arc::strong_count(&item), 4);
+
+        vec.resize_with(0, || unreachable!());
+        assert!(vec.is_empty());
+        assert_eq!(Arc::strong_count(&item), 1);
+    }
+
+    #[test]
+    fn test_split_off() {
+        use std::sync::Arc;
+
+        let item = Arc::new(0);
+        let mut vec: Vec0<Arc<i32>> = (0..5).map(|_| Arc::clone(&item)).collect();
+        let capacity = vec.capacity();
+
+        let tail = vec.split_off(2);
+        assert_eq!(vec.len(), 2);
+        assert_eq!(vec.capacity(), capacity);
+        assert_eq!(tail.len(), 3);
+        // Moved, not cloned
+        assert_eq!(Arc::strong_count(&item), 6);
+
+        drop(tail);
+        assert_eq!(Arc::strong_count(&item), 3);
+        drop(vec);
+        assert_eq!(Arc::strong_count(&item), 1);
+    }
+
+    #[test]
+    fn test_split_off_edges() {
+        let mut vec = vec0![String::from("a"), String::from("b")];
+
+        // At the end: nothing moves
+        let empty = vec.split_off(2);
+        assert!(empty.is_empty());
+        assert_eq!(vec.as_slice(), &["a", "b"]);
+
+        // At the start: everything moves
+        let all = vec.split_off(0);
+        assert!(vec.is_empty());
+        assert_eq!(all.as_slice(), &["a", "b"]);
+
+        // Alignment is carried over to the new vector
+        let mut aligned: Vec0<u8> = Vec0::with_capacity_aligned(4, 64);
+        aligned.extend([1, 2, 3, 4]);
+        let tail = aligned.split_off(1);
+        assert_eq!(tail.as_slice(), &[2, 3, 4]);
+        assert_eq!(tail.as_slice().as_ptr() as usize % 64, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "split_off index out of bounds: 3 > 2")]
+    fn test_split_off_out_of_bounds() {
+        let mut vec = vec0![1, 2];
+        vec.split_off(3);
+    }
+
+    #[test]
+    fn test_append() {
+        use std::sync::Arc;
+
+        let item = Arc::new(0);
+        let mut a: Vec0<Arc<i32>> = (0..2).map(|_| Arc::clone(&item)).collect();
+        let mut b: Vec0<Arc<i32>> = (0..3).map(|_| Arc::clone(&item)).collect();
+        let b_capacity = b.capacity();
+
+        a.append(&mut b);
+        assert_eq!(a.len(), 5);
+        assert_eq!(b.len(), 0);
+        assert_eq!(b.capacity(), b_capacity);
+        // Moved, not cloned
+        assert_eq!(Arc::strong_count(&item), 6);
+
+        // Appending an empty vector changes nothing
+        a.append(&mut b);
+        assert_eq!(a.len(), 5);
+
+        drop(b);
+        assert_eq!(Arc::strong_count(&item), 6);
+        drop(a);
+        assert_eq!(Arc::strong_count(&item), 1);
+    }
+
+    #[test]
+    fn test_reserve() {
+        let mut vec = vec0![1, 2, 3];
+        vec.reserve(100);
+        assert!(vec.capacity() >= 103);
+
+        // The next 100 pushes fit without moving the buffer
+        let ptr = vec.as_slice().as_ptr();
+        let capacity = vec.capacity();
+        for i in 0..100 {
+            vec.push(i);
+        }
+        assert_eq!(vec.as_slice().as_ptr(), ptr);
+        assert_eq!(vec.capacity(), capacity);
+
+        // Reserving what's already there does nothing
+        vec.reserve(0);
+        assert_eq!(vec.capacity(), capacity);
+    }
+
+    #[test]
+    fn test_reserve_exact() {
+        let mut vec: Vec0<String> = Vec0::new();
+        vec.reserve_exact(5);
+        assert_eq!(vec.capacity(), 5);
+
+        vec.push(String::from("a"));
+        vec.reserve_exact(2); // 1 + 2 <= 5, nothing to do
+        assert_eq!(vec.capacity(), 5);
+        vec.reserve_exact(7);
+        assert_eq!(vec.capacity(), 8);
+        assert_eq!(vec.as_slice(), &["a"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn test_reserve_overflow() {
+        let mut vec = vec0![1u8];
+        vec.reserve(usize::MAX);
+    }
+
+    #[test]
+    fn test_zero_sized() {
+        let mut vec: Vec0<()> = Vec0::new();
+        assert_eq!(vec.capacity(), usize::MAX);
+
+        for _ in 0..5_000_000 {
+            vec.push(());
+        }
+        assert_eq!(vec.len(), 5_000_000);
+        assert_eq!(vec.capacity(), usize::MAX);
+
+        vec.insert(0, ());
+        assert_eq!(vec.remove(0), ());
+        assert_eq!(vec.iter_mut().count(), 5_000_000);
+        vec.shrink_to_fit();
+        assert_eq!(vec.capacity(), usize::MAX);
+        vec.truncate(10);
+
+        let mut iter = vec.clone().into_iter();
+        assert_eq!(iter.len(), 10);
+        assert_eq!(iter.next_back(), Some(()));
+        assert_eq!(iter.count(), 9);
+
+        let with_capacity: Vec0<()> = Vec0::with_capacity(10);
+        assert_eq!(with_capacity.capacity(), usize::MAX);
+    }
+
+    #[test]
+    fn test_zero_sized_drop() {
+        use std::cell::Cell;
+
+        thread_local! {
+            static DROPS: Cell<usize> = const { Cell::new(0) };
+        }
+
+        struct Noisy;
+        impl Drop for Noisy {
+            fn drop(&mut self) {
+                DROPS.with(|drops| drops.set(drops.get() + 1));
+            }
+        }
+
+        let mut vec = Vec0::new();
+        for _ in 0..100 {
+            vec.push(Noisy);
+        }
+        vec.truncate(90);
+        assert_eq!(DROPS.with(Cell::get), 10);
+
+        let mut iter = vec.into_iter();
+        iter.next();
+        assert_eq!(DROPS.with(Cell::get), 11);
+        drop(iter);
+        assert_eq!(DROPS.with(Cell::get), 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn test_with_capacity_overflow() {
+        let _vec: Vec0<u64> = Vec0::with_capacity(usize::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn test_with_capacity_aligned_overflow() {
+        let _vec: Vec0<u8> = Vec0::with_capacity_aligned(isize::MAX as usize, 64);
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn test_grow_overflow() {
+        // Doubling this capacity would need more than `isize::MAX` bytes
+        let mut vec: Vec0<[u8; 1024]> = Vec0::with_capacity(1);
+        vec.push([0; 1024]);
+        vec.reserve_exact(isize::MAX as usize / 1024);
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn test_zero_sized_overflow() {
+        let mut vec: Vec0<()> = Vec0::new();
+        vec.push(());
+        vec.reserve(usize::MAX);
+    }
+
+    #[test]
+    fn test_get() {
+        let mut vec = vec0![String::from("a"), String::from("b")];
+        assert_eq!(vec.get(0).map(String::as_str), Some("a"));
+        assert_eq!(vec.get(1).map(String::as_str), Some("b"));
+        assert_eq!(vec.get(2), None);
+        assert_eq!(vec.get(usize::MAX), None);
+
+        vec.get_mut(0).unwrap().push('!');
+        assert_eq!(vec.get_mut(2), None);
+        assert_eq!(vec.as_slice(), &["a!", "b"]);
+
+        let empty: Vec0<i32> = Vec0::new();
+        assert_eq!(empty.get(0), None);
+    }
+
+    #[test]
+    fn test_get_range() {
+        let mut vec = vec0![1, 2, 3, 4];
+        assert_eq!(vec.get(1..3), Some(&[2, 3][..]));
+        assert_eq!(vec.get(..2), Some(&[1, 2][..]));
+        assert_eq!(vec.get(2..), Some(&[3, 4][..]));
+        assert_eq!(vec.get(3..5), None);
+
+        vec.get_mut(..2).unwrap().reverse();
+        assert_eq!(vec.as_slice(), &[2, 1, 3, 4]);
+        assert_eq!(vec.get_mut(5..), None);
+    }
+
+    #[test]
+    fn test_extend_from_slice() {
+        let mut vec = vec0![String::from("a")];
+        let src = [String::from("b"), String::from("c")];
+        vec.extend_from_slice(&src);
+        assert_eq!(vec.as_slice(), &["a", "b", "c"]);
+        assert_eq!(src, ["b", "c"]); // cloned, not moved
+
+        vec.extend_from_slice(&[]);
+        assert_eq!(vec.len(), 3);
+    }
+
+    #[test]
+    fn test_extend_from_slice_panicking_clone() {
+        use std::cell::Cell;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::rc::Rc;
+
+        // Clones panic from the third call on; live values are counted
+        struct Fragile {
+            clones: Rc<Cell<usize>>,
+            live: Rc<Cell<usize>>,
+        }
+        impl Fragile {
+            fn new(clones: &Rc<Cell<usize>>, live: &Rc<Cell<usize>>) -> Fragile {
+                live.set(live.get() + 1);
+                Fragile {
+                    clones: Rc::clone(clones),
+                    live: Rc::clone(live),
+                }
+            }
+        }
+        impl Clone for Fragile {
+            fn clone(&self) -> Fragile {
+                self.clones.set(self.clones.get() + 1);
+                assert!(self.clones.get() < 3, "clone panicked");
+                Fragile::new(&self.clones, &self.live)
+            }
+        }
+        impl Drop for Fragile {
+            fn drop(&mut self) {
+                self.live.set(self.live.get() - 1);
+            }
+        }
+
+        let clones = Rc::new(Cell::new(0));
+        let live = Rc::new(Cell::new(0));
+        let src: Vec<Fragile> = (0..4).map(|_| Fragile::new(&clones, &live)).collect();
+        let mut vec: Vec0<Fragile> = Vec0::new();
+
+        let result = catch_unwind(AssertUnwindSafe(|| vec.extend_from_slice(&src)));
+        assert!(result.is_err());
+
+        // The two successful clones are in the vector, nothing else
+        assert_eq!(vec.len(), 2);
+        assert_eq!(live.get(), 6);
+        drop(vec);
+        drop(src);
+        assert_eq!(live.get(), 0);
+    }
+
+    #[test]
+    fn test_spare_capacity_mut() {
+        let source: &[u8] = b"hello world";
+        let mut vec: Vec0<u8> = Vec0::with_capacity(8);
+        vec.push(b'>');
+
+        // Read exactly 5 bytes straight into the spare capacity, then commit them
+        let n = 5;
+        let spare = vec.spare_capacity_mut();
+        assert_eq!(spare.len(), 7);
+        for (slot, &byte) in spare[..n].iter_mut().zip(source) {
+            slot.write(byte);
+        }
+        unsafe { vec.set_len(vec.len() + n) };
+
+        assert_eq!(vec.as_slice(), b">hello");
+        assert_eq!(vec.capacity(), 8);
+        assert_eq!(vec.spare_capacity_mut().len(), 2);
+    }
+
+    #[test]
+    fn test_set_len_shrink() {
+        let mut vec = vec0![1, 2, 3];
+        // Copy types need no drop, so forgetting the tail is fine
+        unsafe { vec.set_len(1) };
+        assert_eq!(vec.as_slice(), &[1]);
+        assert_eq!(vec.capacity(), 4);
+    }
+
+    #[test]
+    fn test_range_index() {
+        let mut vec = vec0![String::from("a"), String::from("b"), String::from("c")];
+        assert_eq!(&vec[0..2], &["a", "b"]);
+        assert_eq!(&vec[..], &["a", "b", "c"]);
+        assert_eq!(&vec[..1], &["a"]);
+        assert_eq!(&vec[2..], &["c"]);
+        assert_eq!(&vec[1..=2], &["b", "c"]);
+        assert_eq!(&vec[..=0], &["a"]);
+        assert!(vec[3..].is_empty());
+
+        vec[1..].reverse();
+        assert_eq!(vec.as_slice(), &["a", "c", "b"]);
+        for s in &mut vec[..=1] {
+            s.push('!');
+        }
+        assert_eq!(vec.as_slice(), &["a!", "c!", "b"]);
+
+        // Slice patterns work on the result
+        if let [first, .., last] = &vec[..] {
+            assert_eq!((first.as_str(), last.as_str()), ("a!", "b"));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "range end index 4 out of range for slice of length 3")]
+    fn test_range_index_out_of_bounds() {
+        let vec = vec0![1, 2, 3];
+        let _ = &vec[1..4];
+    }
+
+    #[test]
+    fn test_into_boxed_slice() {
+        use std::sync::Arc;
+
+        let item = Arc::new(0);
+        let mut vec: Vec0<Arc<i32>> = Vec0::with_capacity(10);
+        for _ in 0..3 {
+            vec.push(Arc::clone(&item));
+        }
+
+        let boxed = vec.into_boxed_slice();
+        assert_eq!(boxed.len(), 3);
+        // Moved, not cloned
+        assert_eq!(Arc::strong_count(&item), 4);
+        drop(boxed);
+        assert_eq!(Arc::strong_count(&item), 1);
+    }
+
+    #[test]
+    fn test_into_boxed_slice_round_trip() {
+        let vec = vec0![String::from("a"), String::from("b")];
+        let boxed = vec.into_boxed_slice();
+        assert_eq!(&*boxed, &["a", "b"]);
+        assert_eq!(format!("{:?}", boxed), r#"Box0(["a", "b"])"#);
+        let back: Vec0<String> = Vec0::from(&*boxed);
+        assert_eq!(back, vec0![String::from("a"), String::from("b")]);
+
+        // Empty, zero-sized and over-aligned vectors all convert
+        let empty: Vec0<i32> = Vec0::with_capacity(4);
+        assert!(empty.into_boxed_slice().is_empty());
+        let units: Vec0<()> = vec0![(), ()];
+        assert_eq!(units.into_boxed_slice().len(), 2);
+        let mut aligned: Vec0<u8> = Vec0::with_capacity_aligned(4, 64);
+        aligned.extend([1, 2, 3]);
+        assert_eq!(&*aligned.into_boxed_slice(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut vec = Vec0::new();
+        vec.push(1);
+        vec.push(2);
+        vec.push(3);
+
+        vec.clear();
+        assert_eq!(vec.len(), 0);
+        assert!(vec.is_empty());
+    }
+
+    #[test]
+    fn test_shrink_to_fit() {
+        let mut vec = Vec0::with_capacity(10);
+        vec.push(1);
+        vec.push(2);
+
+        assert_eq!(vec.capacity(), 10);
+        vec.shrink_to_fit();
+        assert_eq!(vec.capacity(), 2);
+        assert_eq!(vec.len(), 2);
+    }
+
+    #[test]
+    fn test_deref_to_slice() {
+        let mut vec = Vec0::new();
+        vec.push(1);
+        vec.push(2);
+        vec.push(3);
+
+        let slice: &[i32] = &vec;
+        assert_eq!(slice.len(), 3);
+        assert_eq!(slice[0], 1);
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let mut vec = Vec0::new();
+        vec.push(1);
+        vec.push(2);
+        vec.push(3);
+
+        let mut sum = 0;
+        for val in vec {
+            sum += val;
+        }
+        assert_eq!(sum, 6);
+    }
+
+    #[test]
+    fn test_nth() {
+        let mut vec = vec0![String::from("a"), String::from("b")];
+        assert_eq!(vec.nth(1).map(String::as_str), Some("b"));
+        assert_eq!(vec.nth(2), None);
+
+        vec.nth_mut(0).unwrap().push('!');
+        assert_eq!(vec.as_slice(), &["a!", "b"]);
+        assert!(vec.nth_mut(2).is_none());
+    }
+
+    #[test]
+    fn test_split_at_checked() {
+        let mut vec = vec0![1, 2, 3];
+        assert_eq!(vec.split_at_checked(0), Some((&[][..], &[1, 2, 3][..])));
+        assert_eq!(vec.split_at_checked(3), Some((&[1, 2, 3][..], &[][..])));
+        assert_eq!(vec.split_at_checked(4), None);
+
+        let (left, right) = vec.split_at_mut_checked(1).unwrap();
+        std::mem::swap(&mut left[0], &mut right[1]);
+        assert_eq!(vec.as_slice(), &[3, 2, 1]);
+        assert!(vec.split_at_mut_checked(4).is_none());
+
+        let empty: Vec0<i32> = Vec0::new();
+        assert!(empty.split_at_checked(0).is_some());
+        assert!(empty.split_at_checked(1).is_none());
+    }
+
+    #[test]
+    fn test_first_last_mut() {
+        let mut vec = vec0![String::from("a"), String::from("b")];
+        vec.first_mut().unwrap().push('1');
+        vec.last_mut().unwrap().push('2');
+        assert_eq!(vec.as_slice(), &["a1", "b2"]);
+
+        let mut single = vec0![5];
+        *single.first_mut().unwrap() += 1;
+        assert_eq!(single.last_mut(), Some(&mut 6));
+
+        let mut empty: Vec0<i32> = Vec0::new();
+        assert!(empty.first_mut().is_none());
+        assert!(empty.last_mut().is_none());
+    }
+
+    #[test]
+    fn test_into_iter_clone() {
+        let mut iter = vec0![String::from("a"), String::from("b"), String::from("c")].into_iter();
+        assert_eq!(iter.next().as_deref(), Some("a"));
+
+        let replay = iter.clone();
+        assert_eq!(replay.size_hint(), (2, Some(2)));
+        assert_eq!(replay.capacity, 2); // only the unconsumed tail was copied
+        assert_eq!(iter.collect::<Vec<_>>(), vec!["b", "c"]);
+        assert_eq!(replay.collect::<Vec<_>>(), vec!["b", "c"]);
+
+        let mut drained = vec0![1].into_iter();
+        drained.next();
+        assert_eq!(drained.clone().next(), None);
+    }
+
+    #[test]
+    fn test_iterator_debug_shows_remaining() {
+        let mut into_iter = vec0![String::from("a"), String::from("b")].into_iter();
+        assert_eq!(format!("{:?}", into_iter), r#"IntoIter(["a", "b"])"#);
+        into_iter.next();
+        assert_eq!(format!("{:?}", into_iter), r#"IntoIter(["b"])"#);
+        into_iter.next();
+        assert_eq!(format!("{:?}", into_iter), "IntoIter([])");
+
+        let mut vec = vec0![1, 2, 3];
+        let mut iter_mut = vec.iter_mut();
+        let first = iter_mut.next().unwrap();
+        *first = 10;
+        assert_eq!(format!("{:?}", iter_mut), "IterMut([2, 3])");
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut vec = vec0![
+            String::from("a"),
+            String::from("b"),
+            String::from("c"),
+            String::from("d")
+        ];
+        let mut drain = vec.drain(1..=2);
+        assert_eq!(drain.size_hint(), (2, Some(2)));
+        assert_eq!(drain.next(), Some(String::from("b")));
+        assert_eq!(drain.next(), Some(String::from("c")));
+        assert_eq!(drain.next(), None);
+        drop(drain);
+        assert_eq!(vec.as_slice(), &["a", "d"]);
+
+        // Usable again afterwards
+        vec.push(String::from("e"));
+        assert_eq!(vec.as_slice(), &["a", "d", "e"]);
+        assert!(vec.len() <= vec.capacity());
+
+        let all: Vec<_> = vec.drain(..).collect();
+        assert_eq!(all, vec!["a", "d", "e"]);
+        assert!(vec.is_empty());
+
+        assert_eq!(vec.drain(..).next(), None);
+    }
+
+    #[test]
+    fn test_drain_drops_unyielded() {
+        use std::sync::Arc;
+
+        let items: Vec<Arc<i32>> = (0..5).map(Arc::new).collect();
+        let mut vec: Vec0<Arc<i32>> = items.iter().cloned().collect();
+
+        let mut drain = vec.drain(1..4);
+        let first = drain.next().unwrap();
+        drop(drain); // 2 and 3 never yielded
+        assert_eq!(Arc::strong_count(&items[2]), 1);
+        assert_eq!(Arc::strong_count(&items[3]), 1);
+        drop(first);
+
+        assert_eq!(vec.iter().map(|x| **x).collect::<Vec<_>>(), vec![0, 4]);
+        drop(vec);
+        assert!(items.iter().all(|item| Arc::strong_count(item) == 1));
+    }
+
+    #[test]
+    fn test_drain_forget_does_not_double_free() {
+        use std::sync::Arc;
+
+        let item = Arc::new(0);
+        let mut vec = vec0![item.clone(), item.clone(), item.clone()];
+
+        let mut drain = vec.drain(..2);
+        drain.next();
+        std::mem::forget(drain);
+
+        // The vector gave up everything from the start of the range:
+        // the rest leaks instead of being dropped twice
+        assert!(vec.is_empty());
+        drop(vec);
+        assert_eq!(Arc::strong_count(&item), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "drain end out of bounds: 4 > 3")]
+    fn test_drain_out_of_bounds() {
+        let mut vec = vec0![1, 2, 3];
+        vec.drain(1..4);
+    }
+
+    #[test]
+    #[should_panic(expected = "attempted to index slice up to maximum usize")]
+    fn test_drain_inclusive_end_overflow() {
+        let mut vec = vec0![1, 2, 3];
+        vec.drain(..=usize::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "attempted to index slice from after maximum usize")]
+    fn test_drain_excluded_start_overflow() {
+        use std::ops::Bound;
+
+        let mut vec = vec0![1, 2, 3];
+        vec.drain((Bound::Excluded(usize::MAX), Bound::Unbounded));
+    }
+
+    #[test]
+    fn test_into_iter_double_ended() {
+        let vec = vec0![1, 2, 3, 4, 5];
+        let mut iter = vec.into_iter();
+        assert_eq!(iter.len(), 5);
+        assert_eq!(iter.next_back(), Some(5));
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.size_hint(), (3, Some(3)));
+
+        let rest: Vec<i32> = iter.rev().collect();
+        assert_eq!(rest, vec![4, 3, 2]);
+
+        let chained: Vec<i32> = vec0![1, 2].into_iter().chain(vec0![3]).rev().collect();
+        assert_eq!(chained, vec![3, 2, 1]);
+
+        // Meeting in the middle ends iteration from both sides
+        let mut iter = vec0![1, 2].into_iter();
+        assert_eq!(iter.next_back(), Some(2));
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.len(), 0);
+    }
+
+    #[test]
+    fn test_into_iter_drop_after_both_ends() {
+        use std::rc::Rc;
+
+        let tracker = Rc::new(());
+        let vec: Vec0<Rc<()>> = (0..5).map(|_| Rc::clone(&tracker)).collect();
+        assert_eq!(Rc::strong_count(&tracker), 6);
+
+        let mut iter = vec.into_iter();
+        let front = iter.next().unwrap();
+        let back = iter.next_back().unwrap();
+        drop(iter); // drops the three in the middle, and only those
+
+        assert_eq!(Rc::strong_count(&tracker), 3);
+        drop(front);
+        drop(back);
+        assert_eq!(Rc::strong_count(&tracker), 1);
+    }
+
+    #[test]
+    fn test_borrowing_into_iter() {
+        let mut vec = vec0![String::from("a"), String::from("b")];
+
+        let mut seen = Vec::new();
+        for s in &vec {
+            seen.push(s.clone());
+        }
+        assert_eq!(seen, vec!["a", "b"]);
+        assert_eq!(vec.len(), 2); // still usable
+
+        for s in &mut vec {
+            s.push('!');
+        }
+        assert_eq!(vec.as_slice(), &["a!", "b!"]);
+
+        // The consuming impl still applies to the vector itself
+        let owned: Vec<String> = vec.into_iter().collect();
+        assert_eq!(owned, vec!["a!", "b!"]);
+    }
+
+    #[test]
+    fn test_iter_mut() {
+        let mut vec = Vec0::new();
+        vec.push(1);
+        vec.push(2);
+        vec.push(3);
+
+        let mut iter = vec.iter_mut();
+        assert_eq!(iter.size_hint(), (3, Some(3)));
+        for x in iter.by_ref().take(2) {
+            *x *= 10;
+        }
+        assert_eq!(iter.size_hint(), (1, Some(1)));
+        assert_eq!(iter.next(), Some(&mut 3));
+        assert_eq!(iter.next(), None);
+
+        assert_eq!(vec.as_slice(), &[10, 20, 3]);
+
+        let mut empty: Vec0<i32> = Vec0::new();
+        assert_eq!(empty.iter_mut().next(), None);
+    }
+
+    #[test]
+    fn test_iter_mut_double_ended() {
+        let mut vec = vec0![1, 2, 3, 4];
+        let mut iter = vec.iter_mut();
+        assert_eq!(iter.len(), 4);
+        *iter.next_back().unwrap() = 40;
+        *iter.next().unwrap() = 10;
+        assert_eq!(iter.len(), 2);
+
+        // Both ends meet in the middle without handing out a slot twice
+        let rest: Vec<&mut i32> = iter.rev().collect();
+        assert_eq!(rest, vec![&mut 3, &mut 2]);
+        assert_eq!(vec.as_slice(), &[10, 2, 3, 40]);
+
+        let mut zst: Vec0<()> = vec0![(), ()];
+        assert_eq!(zst.iter_mut().rev().count(), 2);
+    }
+
+    #[test]
+    fn test_extend_from_copy_slice() {
+        let mut vec = vec0![1];
+        vec.extend_from_copy_slice(&[2, 3]);
+        assert_eq!(vec.as_slice(), &[1, 2, 3]);
+        assert_eq!(vec.capacity(), 3);
+
+        vec.extend_from_copy_slice(&[]);
+        assert_eq!(vec.len(), 3);
+
+        // Copying a vector's own contents onto its end
+        let copy = vec.clone();
+        vec.extend_from_copy_slice(&copy);
+        assert_eq!(vec.as_slice(), &[1, 2, 3, 1, 2, 3]);
+
+        let mut empty: Vec0<u8> = Vec0::new();
+        empty.extend_from_copy_slice(b"hi");
+        assert_eq!(empty.as_slice(), b"hi");
+
+        // The slice method is no longer shadowed: it overwrites in place
+        empty.copy_from_slice(b"yo");
+        assert_eq!(empty.as_slice(), b"yo");
+    }
+
+    #[test]
+    fn test_try_reserve() {
+        let mut vec = vec0![1, 2];
+        assert_eq!(vec.try_reserve(3), Result0::Ok(()));
+        assert!(vec.capacity() >= 5);
+
+        // Too large to describe as a layout: fails without touching the vector
+        let capacity = vec.capacity();
+        assert_eq!(
+            vec.try_reserve(usize::MAX),
+            Result0::Err(TryReserveError::CapacityOverflow)
+        );
+        assert_eq!(
+            vec.try_reserve(isize::MAX as usize),
+            Result0::Err(TryReserveError::CapacityOverflow)
+        );
+        assert_eq!(vec.as_slice(), &[1, 2]);
+        assert_eq!(vec.capacity(), capacity);
+    }
+
+    #[test]
+    fn test_try_reserve_exact() {
+        let mut vec = vec0![String::from("a")];
+        assert_eq!(vec.try_reserve_exact(2), Result0::Ok(()));
+        assert_eq!(vec.capacity(), 3);
+        assert_eq!(vec.try_reserve_exact(1), Result0::Ok(()));
+        assert_eq!(vec.capacity(), 3);
+
+        assert_eq!(
+            vec.try_reserve_exact(usize::MAX),
+            Result0::Err(TryReserveError::CapacityOverflow)
+        );
+        assert_eq!(vec.as_slice(), &["a"]);
+        assert_eq!(vec.capacity(), 3);
+    }
+
+    #[test]
+    fn test_try_reserve_alloc_error() {
+        // A valid layout, but far more memory than any allocator will hand out
+        let mut vec: Vec0<u8> = Vec0::new();
+        let additional = isize::MAX as usize;
+        match vec.try_reserve_exact(additional) {
+            Result0::Err(TryReserveError::AllocError { layout }) => {
+                assert_eq!(layout.size(), additional);
+            }
+            other => panic!("expected AllocError, got {:?}", other),
+        }
+        assert_eq!(vec.capacity(), 0);
+
+        // The vector still works afterwards
+        vec.push(1);
+        assert_eq!(vec.as_slice(), &[1]);
+    }
+
+    #[test]
+    fn test_try_extend_from_slice() {
+        let mut vec = vec0![String::from("a")];
+        let src = [String::from("b"), String::from("c")];
+        assert_eq!(vec.try_extend_from_slice(&src), Result0::Ok(()));
+        assert_eq!(vec.as_slice(), &["a", "b", "c"]);
+        assert_eq!(vec.capacity(), 3);
+
+        assert_eq!(vec.try_extend_from_slice(&[]), Result0::Ok(()));
+        assert_eq!(vec.len(), 3);
+    }
+
+    #[test]
+    fn test_extend_with() {
+        // Not Clone, so vec0![x; n] couldn't build these
+        struct Id(usize);
+
+        let mut vec: Vec0<Id> = Vec0::new();
+        let mut next = 0;
+        vec.extend_with(5, || {
+            next += 1;
+            Id(next)
+        });
+        assert_eq!(vec.len(), 5);
+        assert_eq!(vec.capacity(), 5); // reserved once
+        assert_eq!(vec.iter().map(|id| id.0).sum::<usize>(), 15);
+
+        vec.extend_with(0, || unreachable!());
+        assert_eq!(vec.len(), 5);
+    }
+
+    #[test]
+    fn test_flat_map() {
+        let vec = vec0![1, 2, 3];
+        let result = vec.flat_map(|x| vec0![x; x]);
+        assert_eq!(result.as_slice(), &[1, 2, 2, 3, 3, 3]);
+
+        // Exact size hints: a single allocation of the right size
+        let vec = vec0![0, 4];
+        let result = vec.flat_map(|x| x..x + 4);
+        assert_eq!(result.as_slice(), &[0, 1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(result.capacity(), 8);
+
+        // No useful size hint: falls back to plain pushes
+        let vec = vec0![String::from("a b"), String::from("c")];
+        let words = vec.flat_map(|s| {
+            let parts: Vec<String> = s.split(' ').map(String::from).collect();
+            parts.into_iter().filter(|w| !w.is_empty())
+        });
+        assert_eq!(words.as_slice(), &["a", "b", "c"]);
+
+        let empty: Vec0<i32> = Vec0::new();
+        assert!(empty.flat_map(|x| vec0![x]).is_empty());
+    }
+
+    #[test]
+    fn test_partition() {
+        use std::sync::Arc;
+
+        let (even, odd) = vec0![1, 2, 3, 4, 5, 6].partition(|x| x % 2 == 0);
+        assert_eq!(even.as_slice(), &[2, 4, 6]);
+        assert_eq!(odd.as_slice(), &[1, 3, 5]);
+
+        // Elements are moved: no extra Arc references are created
+        let item = Arc::new(0);
+        let vec = vec0![item.clone(), item.clone(), item.clone()];
+        let (first, others) = vec.partition(|x| Arc::strong_count(x) > 100);
+        assert!(first.is_empty());
+        assert_eq!(others.len(), 3);
+        assert_eq!(Arc::strong_count(&item), 4);
+        drop(others);
+        assert_eq!(Arc::strong_count(&item), 1);
+    }
+
+    #[test]
+    fn test_unzip() {
+        let vec = vec0![(1, String::from("one")), (2, String::from("two"))];
+        let (nums, names) = vec.unzip();
+        assert_eq!(nums.as_slice(), &[1, 2]);
+        assert_eq!(names.as_slice(), &["one", "two"]);
+        assert_eq!(nums.capacity(), 2);
+        assert_eq!(names.capacity(), 2);
+
+        let empty: Vec0<(i32, i32)> = Vec0::new();
+        let (a, b) = empty.unzip();
+        assert!(a.is_empty());
+        assert!(b.is_empty());
+    }
+
+    #[test]
+    fn test_flatten_refs() {
+        let words = [String::from("a"), String::from("b"), String::from("c")];
+        let parts: Vec0<&[String]> = vec0![&words[..1], &words[1..]];
+        let flat = parts.flatten_refs();
+        assert_eq!(flat.as_slice(), &["a", "b", "c"]);
+        assert_eq!(flat.capacity(), 3);
+
+        let nums = [1, 2, 3, 4];
+        let parts: Vec0<&[i32]> = vec0![&nums[2..], &nums[..0], &nums[..2]];
+        let flat = parts.flatten_refs_copied();
+        assert_eq!(flat.as_slice(), &[3, 4, 1, 2]);
+        assert_eq!(flat.capacity(), 4);
+
+        let empty: Vec0<&[i32]> = Vec0::new();
+        assert!(empty.flatten_refs().is_empty());
+    }
+
+    #[test]
+    fn test_from_ref_conversions() {
+        let vec = vec0![1, 2, 3];
+        let std_vec: Vec<i32> = Vec::from(&vec);
+        assert_eq!(std_vec, vec![1, 2, 3]);
+        assert_eq!(vec.len(), 3); // Still usable
+
+        let back: Vec0<i32> = Vec0::from(&std_vec);
+        assert_eq!(back.as_slice(), std_vec.as_slice());
+        assert_eq!(std_vec.len(), 3); // Still usable
+
+        let empty: Vec0<i32> = (&Vec::new()).into();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_linked_list_conversions() {
+        let vec = vec0![String::from("a"), String::from("b"), String::from("c")];
+        let list = LinkedList::from(vec);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.front().map(String::as_str), Some("a"));
+
+        let back = Vec0::from(list);
+        assert_eq!(back.as_slice(), &["a", "b", "c"]);
+        assert_eq!(back.capacity(), 3);
+
+        let empty: Vec0<i32> = LinkedList::new().into();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_sort_dedup() {
+        let mut vec = vec0![5, 1, 3, 1, 5, 5, 2];
+        vec.sort_dedup();
+        assert_eq!(vec.as_slice(), &[1, 2, 3, 5]);
+
+        let mut strings = vec0![String::from("b"), String::from("a"), String::from("b")];
+        strings.sort_dedup();
+        assert_eq!(strings.as_slice(), &["a", "b"]);
+
+        let mut empty: Vec0<i32> = Vec0::new();
+        empty.sort_dedup();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_sort_dedup_by() {
+        let mut vec = vec0![-3, 2, 3, -2, 1];
+        vec.sort_dedup_by(|a: &i32, b: &i32| a.abs().cmp(&b.abs()));
+        assert_eq!(vec.len(), 3);
+        assert_eq!(vec[0], 1);
+        assert_eq!(vec[1].abs(), 2);
+        assert_eq!(vec[2].abs(), 3);
+    }
+
+    #[test]
+    fn test_min_max() {
+        let vec = vec0![4, 1, 7, 1, 3];
+        assert_eq!(vec.min(), Some(&1));
+        assert_eq!(vec.max(), Some(&7));
+
+        let empty: Vec0<i32> = Vec0::new();
+        assert_eq!(empty.min(), None);
+        assert_eq!(empty.max(), None);
+    }
+
+    #[test]
+    fn test_min_max_by_key() {
+        let vec = vec0![(1, "b"), (2, "a"), (3, "a"), (4, "c")];
+        // Ties: min returns the first, max returns the last
+        assert_eq!(vec.min_by_key(|pair| pair.1), Some(&(2, "a")));
+        assert_eq!(vec.max_by_key(|pair| pair.1), Some(&(4, "c")));
+        assert_eq!(vec.max_by_key(|pair| pair.1 != "c"), Some(&(3, "a")));
+
+        let empty: Vec0<i32> = Vec0::new();
+        assert_eq!(empty.min_by_key(|x| *x), None);
+    }
+
+    #[test]
+    fn test_sum_product() {
+        let vec = vec0![1, 2, 3, 4, 5];
+        assert_eq!(vec.sum::<i32>(), 15);
+        assert_eq!(vec.product::<i32>(), 120);
+
+        let floats = vec0![0.5, 1.5, 2.0];
+        assert_eq!(floats.sum::<f64>(), 4.0);
+        assert_eq!(floats.product::<f64>(), 1.5);
+
+        // Empty sum is 0, empty product is 1
+        let empty: Vec0<i32> = Vec0::new();
+        assert_eq!(empty.sum::<i32>(), 0);
+        assert_eq!(empty.product::<i32>(), 1);
+    }
+
+    #[test]
+    fn test_count_by_any_all() {
+        let vec = vec0![1, 2, 3, 4, 5];
+        assert_eq!(vec.count_by(|x| *x > 2), 3);
+        assert!(vec.any(|x| *x == 4));
+        assert!(vec.all(|x| *x > 0));
+
+        // any stops at the first match, all at the first miss
+        let mut calls = 0;
+        assert!(vec.any(|x| {
+            calls += 1;
+            *x == 2
+        }));
+        assert_eq!(calls, 2);
+
+        calls = 0;
+        assert!(!vec.all(|x| {
+            calls += 1;
+            *x < 3
+        }));
+        assert_eq!(calls, 3);
+
+        let empty: Vec0<i32> = Vec0::new();
+        assert_eq!(empty.count_by(|_| true), 0);
+        assert!(!empty.any(|_| true));
+        assert!(empty.all(|_| false));
+    }
+
+    #[test]
+    fn test_range_collect() {
+        let v = Vec0::range_collect(0..5);
+        assert_eq!(v.as_slice(), &[0, 1, 2, 3, 4]);
+        assert_eq!(v.capacity(), 5);
+
+        let chars = Vec0::range_collect('a'..'d');
+        assert_eq!(chars.as_slice(), &['a', 'b', 'c']);
+
+        let empty = Vec0::range_collect(5..5);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_from_iterator() {
+        let v: Vec0<String> = ["a", "b"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(v.as_slice(), &["a".to_string(), "b".to_string()]);
+
+        let filtered: Vec0<i32> = (0..10).filter(|x| x % 3 == 0).collect();
+        assert_eq!(filtered.as_slice(), &[0, 3, 6, 9]);
+    }
+
+    #[test]
+    fn test_take_while_pop() {
+        let mut v = vec0![1, 2, 3, 4, 5];
+        let taken = v.take_while_pop(|x| *x > 2);
+        assert_eq!(taken.as_slice(), &[3, 4, 5]);
+        assert_eq!(v.as_slice(), &[1, 2]);
+
+        // Nothing matches: self is unchanged
+        let none = v.take_while_pop(|x| *x > 100);
+        assert!(none.is_empty());
+        assert_eq!(v.as_slice(), &[1, 2]);
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        let remaining = self.len - self.index;
-        (remaining, Some(remaining))
+        // Everything matches: self is drained
+        let all = v.take_while_pop(|_| true);
+        assert_eq!(all.as_slice(), &[1, 2]);
+        assert!(v.is_empty());
     }
-}
 
-/// Dropping [`IntoIter`] drops remaining unconsumed elements and frees memory.
-/// ```
-/// use rustlib::vec0;
-/// let v = vec0![String::from("a"), String::from("b")];
-/// let mut iter = v.into_iter();
-/// assert_eq!(iter.next(), Some(String::from("a")));
-/// // iter dropped, "b" is dropped and memory freed
-/// ```
-impl<T> Drop for IntoIter<T> {
-    fn drop(&mut self) {
-        // Drop remaining elements that weren't consumed
-        while self.index < self.len {
-            unsafe {
-                ptr::drop_in_place(self.ptr.add(self.index));
-            }
-            self.index += 1;
-        }
-        // Deallocate memory
-        if self.capacity > 0 {
-            unsafe {
-                let layout = Layout::array::<T>(self.capacity).unwrap();
-                dealloc(self.ptr as *mut u8, layout);
-            }
-        }
+    #[test]
+    fn test_group_by() {
+        let vec = vec0![1, 2, 3, 7, 8, 10];
+        // Runs of consecutive integers
+        let runs = vec.group_by(|a, b| a + 1 == *b);
+        assert_eq!(runs.len(), 3);
+        assert_eq!(runs[0], &[1, 2, 3]);
+        assert_eq!(runs[1], &[7, 8]);
+        assert_eq!(runs[2], &[10]);
+
+        // The groups point into the original allocation
+        assert_eq!(runs[1].as_ptr(), &vec[3] as *const i32);
+
+        let single = vec0!["x"];
+        assert_eq!(single.group_by(|_, _| false).len(), 1);
+
+        let empty: Vec0<i32> = Vec0::new();
+        assert!(empty.group_by(|a, b| a == b).is_empty());
     }
-}
 
-/// Converting [`Vec0`] into an iterator yields owned elements.
-/// ```
-/// use rustlib::vec0;
-/// let v = vec0![1, 2, 3];
-/// let mut sum = 0;
-/// for val in v {
-///     sum += val; // Takes ownership of each element
-/// }
-/// assert_eq!(sum, 6);
-/// // v is consumed, can't be used anymore
-/// ```
-impl<T> IntoIterator for Vec0<T> {
-    type Item = T;
-    type IntoIter = IntoIter<T>;
+    #[test]
+    fn test_retain() {
+        let mut vec = vec0![
+            String::from("keep"),
+            String::from("drop"),
+            String::from("keep2")
+        ];
+        let capacity = vec.capacity();
+        vec.retain(|s| s.starts_with("keep"));
+        assert_eq!(vec.as_slice(), &["keep", "keep2"]);
+        assert_eq!(vec.capacity(), capacity);
 
-    fn into_iter(self) -> Self::IntoIter {
-        let iter = IntoIter {
-            ptr: self.ptr,
-            len: self.len,
-            capacity: self.capacity,
-            index: 0,
-        };
-        // Prevent the original vec from dropping
-        std::mem::forget(self);
-        iter
+        vec.retain(|_| false);
+        assert!(vec.is_empty());
     }
-}
 
-// ============================================================================
-// vec! macro - syntactic sugar for creating vectors
-// ============================================================================
+    #[test]
+    fn test_retain_drops_each_element_once() {
+        use std::sync::Arc;
 
-/// Creates a [`Vec0`] containing the given elements.
-///
-/// ```
-/// use rustlib::vec0;
-/// use rustlib::vec::Vec0;
-/// // Empty vector
-/// let v: Vec0<i32> = vec0![];
-///
-/// // Vector with elements
-/// let v = vec0![1, 2, 3];
-///
-/// // Vector with n copies of an element
-/// let v = vec0![0; 5]; // [0, 0, 0, 0, 0]
-/// ```
-#[macro_export]
-macro_rules! vec0 {
-    () => {
-        $crate::Vec0::new()
-    };
-    ($elem:expr; $n:expr) => {{
-        let mut v = $crate::Vec0::with_capacity($n);
-        #[allow(clippy::reversed_empty_ranges)]
-        for _ in 0..$n {
-            v.push($elem.clone());
+        let items: Vec<Arc<i32>> = (0..6).map(Arc::new).collect();
+        let mut vec: Vec0<Arc<i32>> = items.iter().cloned().collect();
+
+        vec.retain(|x| **x % 3 == 0);
+        assert_eq!(vec.iter().map(|x| **x).collect::<Vec<_>>(), vec![0, 3]);
+        for (i, item) in items.iter().enumerate() {
+            let expected = if i % 3 == 0 { 2 } else { 1 };
+            assert_eq!(Arc::strong_count(item), expected);
         }
-        v
-    }};
-    ($($x:expr),+ $(,)?) => {{
-        let mut v = $crate::Vec0::new();
-        $(v.push($x);)*
-        v
-    }};
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        drop(vec);
+        assert!(items.iter().all(|item| Arc::strong_count(item) == 1));
+    }
 
     #[test]
-    fn test_new() {
-        let vec: Vec0<i32> = Vec0::new();
-        assert_eq!(vec.len(), 0);
-        assert_eq!(vec.capacity(), 0);
-        assert!(vec.is_empty());
+    fn test_retain_panic_safety() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::sync::Arc;
+
+        let items: Vec<Arc<i32>> = (0..5).map(Arc::new).collect();
+        let mut vec: Vec0<Arc<i32>> = items.iter().cloned().collect();
+
+        // Drops 1, keeps 0 and 2, then panics on 3 before deciding
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            vec.retain(|x| {
+                assert!(**x != 3, "predicate panicked");
+                **x != 1
+            })
+        }));
+        assert!(result.is_err());
+
+        // The unvisited 3 and 4 were shifted down, nothing leaked or doubly dropped
+        assert_eq!(
+            vec.iter().map(|x| **x).collect::<Vec<_>>(),
+            vec![0, 2, 3, 4]
+        );
+        assert_eq!(Arc::strong_count(&items[1]), 1);
+        drop(vec);
+        assert!(items.iter().all(|item| Arc::strong_count(item) == 1));
     }
 
     #[test]
-    fn test_with_capacity() {
-        let vec: Vec0<i32> = Vec0::with_capacity(10);
-        assert_eq!(vec.len(), 0);
-        assert_eq!(vec.capacity(), 10);
+    fn test_retain_mut() {
+        let mut vec = vec0![1, 2, 3, 4, 5, 6];
+        vec.retain_mut(|x| {
+            *x *= 10;
+            *x % 20 != 0
+        });
+        assert_eq!(vec.as_slice(), &[10, 30, 50]);
     }
 
     #[test]
-    fn test_push_and_pop() {
-        let mut vec = Vec0::new();
-        vec.push(1);
-        vec.push(2);
-        vec.push(3);
+    fn test_retain_mut_panic_safety() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::sync::Arc;
 
-        assert_eq!(vec.len(), 3);
-        assert_eq!(vec.pop(), Some(3));
-        assert_eq!(vec.pop(), Some(2));
-        assert_eq!(vec.pop(), Some(1));
-        assert_eq!(vec.pop(), None);
+        let items: Vec<Arc<i32>> = (0..5).map(Arc::new).collect();
+        let mut vec: Vec0<Arc<i32>> = items.iter().cloned().collect();
+
+        // Keeps 0, drops 1, panics on the third element
+        let mut calls = 0;
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            vec.retain_mut(|x| {
+                calls += 1;
+                assert!(calls != 3, "predicate panicked");
+                **x != 1
+            })
+        }));
+        assert!(result.is_err());
+
+        assert_eq!(
+            vec.iter().map(|x| **x).collect::<Vec<_>>(),
+            vec![0, 2, 3, 4]
+        );
+        assert_eq!(Arc::strong_count(&items[1]), 1);
+        assert!(items
+            .iter()
+            .enumerate()
+            .all(|(i, item)| Arc::strong_count(item) == if i == 1 { 1 } else { 2 }));
+        drop(vec);
+        assert!(items.iter().all(|item| Arc::strong_count(item) == 1));
     }
 
     #[test]
-    fn test_growth() {
-        let mut vec = Vec0::new();
-        assert_eq!(vec.capacity(), 0);
+    fn test_extract_if() {
+        let mut vec = vec0![
+            String::from("keep"),
+            String::from("take 1"),
+            String::from("keep"),
+            String::from("take 2"),
+        ];
+        let taken: Vec<String> = vec.extract_if(|s| s.starts_with("take")).collect();
+        assert_eq!(taken, vec!["take 1", "take 2"]);
+        assert_eq!(vec.as_slice(), &["keep", "keep"]);
 
-        vec.push(1);
-        assert_eq!(vec.capacity(), 1);
+        // `f` may change the elements it keeps
+        let mut vec = vec0![1, 2, 3];
+        let odd: Vec<i32> = vec
+            .extract_if(|x| {
+                *x *= 10;
+                *x % 20 != 0
+            })
+            .collect();
+        assert_eq!(odd, vec![10, 30]);
+        assert_eq!(vec.as_slice(), &[20]);
+    }
 
-        vec.push(2);
-        assert_eq!(vec.capacity(), 2);
+    #[test]
+    fn test_extract_if_dropped_early() {
+        use std::sync::Arc;
 
-        vec.push(3);
-        assert_eq!(vec.capacity(), 4);
+        let items: Vec<Arc<i32>> = (0..6).map(Arc::new).collect();
+        let mut vec: Vec0<Arc<i32>> = items.iter().cloned().collect();
+
+        let mut iter = vec.extract_if(|x| **x % 2 == 1);
+        let first = iter.next().unwrap();
+        assert_eq!(*first, 1);
+        drop(iter);
+
+        // Only 1 was extracted: the unvisited 3 and 5 stay in place
+        assert_eq!(
+            vec.iter().map(|x| **x).collect::<Vec<_>>(),
+            vec![0, 2, 3, 4, 5]
+        );
+        drop(first);
+        assert_eq!(Arc::strong_count(&items[1]), 1);
+        drop(vec);
+        assert!(items.iter().all(|item| Arc::strong_count(item) == 1));
     }
 
     #[test]
-    fn test_index() {
-        let mut vec = Vec0::new();
-        vec.push(10);
-        vec.push(20);
-        vec.push(30);
+    fn test_extract_if_panic_safety() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::sync::Arc;
 
-        assert_eq!(vec[0], 10);
-        assert_eq!(vec[1], 20);
-        assert_eq!(vec[2], 30);
+        let items: Vec<Arc<i32>> = (0..5).map(Arc::new).collect();
+        let mut vec: Vec0<Arc<i32>> = items.iter().cloned().collect();
+
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            vec.extract_if(|x| {
+                assert!(**x != 3, "predicate panicked");
+                **x == 1
+            })
+            .count()
+        }));
+        assert!(result.is_err());
+
+        assert_eq!(
+            vec.iter().map(|x| **x).collect::<Vec<_>>(),
+            vec![0, 2, 3, 4]
+        );
+        assert_eq!(Arc::strong_count(&items[1]), 1);
+        drop(vec);
+        assert!(items.iter().all(|item| Arc::strong_count(item) == 1));
     }
 
     #[test]
-    fn test_index_mut() {
-        let mut vec = Vec0::new();
-        vec.push(10);
-        vec.push(20);
+    fn test_dedup_consecutive_by_key() {
+        let mut vec = vec0![
+            String::from("apple"),
+            String::from("avocado"),
+            String::from("banana"),
+            String::from("apricot"),
+        ];
+        vec.dedup_consecutive_by_key(|s| s.chars().next());
+        // "apricot" survives: it is not next to the other 'a' words
+        assert_eq!(vec.as_slice(), &["apple", "banana", "apricot"]);
 
-        vec[0] = 100;
-        assert_eq!(vec[0], 100);
+        let mut empty: Vec0<i32> = Vec0::new();
+        empty.dedup_consecutive_by_key(|x| *x);
+        assert!(empty.is_empty());
     }
 
     #[test]
-    #[should_panic(expected = "index out of bounds")]
-    fn test_index_out_of_bounds() {
-        let vec: Vec0<i32> = Vec0::new();
-        let _ = vec[0];
+    fn test_dedup() {
+        let mut vec = vec0![
+            String::from("a"),
+            String::from("a"),
+            String::from("b"),
+            String::from("a"),
+            String::from("a"),
+        ];
+        vec.dedup();
+        assert_eq!(vec.as_slice(), &["a", "b", "a"]);
+
+        let mut single = vec0![String::from("x")];
+        single.dedup();
+        assert_eq!(single.as_slice(), &["x"]);
     }
 
     #[test]
-    fn test_insert() {
-        let mut vec = Vec0::new();
-        vec.push(1);
-        vec.push(3);
-        vec.insert(1, 2);
+    fn test_dedup_by() {
+        use std::sync::Arc;
 
-        assert_eq!(vec[0], 1);
-        assert_eq!(vec[1], 2);
-        assert_eq!(vec[2], 3);
+        let item = Arc::new(0);
+        let mut vec: Vec0<(i32, Arc<i32>)> = [1, 1, 1, 2, 2, 3]
+            .into_iter()
+            .map(|n| (n, Arc::clone(&item)))
+            .collect();
+        let capacity = vec.capacity();
+
+        vec.dedup_by(|a, b| a.0 == b.0);
+        assert_eq!(vec.iter().map(|x| x.0).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(vec.capacity(), capacity);
+        // The three removed duplicates were dropped
+        assert_eq!(Arc::strong_count(&item), 4);
+        drop(vec);
+        assert_eq!(Arc::strong_count(&item), 1);
     }
 
     #[test]
-    fn test_remove() {
-        let mut vec = Vec0::new();
-        vec.push(1);
-        vec.push(2);
-        vec.push(3);
+    fn test_dedup_by_panic_safety() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::sync::Arc;
 
-        assert_eq!(vec.remove(1), 2);
-        assert_eq!(vec.len(), 2);
-        assert_eq!(vec[0], 1);
-        assert_eq!(vec[1], 3);
+        let items: Vec<Arc<i32>> = [0, 0, 1, 2, 2].into_iter().map(Arc::new).collect();
+        let mut vec: Vec0<Arc<i32>> = items.iter().cloned().collect();
+
+        // Drops the second 0, keeps 1, then panics comparing 2 with 1
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            vec.dedup_by(|current, previous| {
+                assert!(**current != 2, "same_bucket panicked");
+                current == previous
+            })
+        }));
+        assert!(result.is_err());
+
+        assert_eq!(
+            vec.iter().map(|x| **x).collect::<Vec<_>>(),
+            vec![0, 1, 2, 2]
+        );
+        assert_eq!(Arc::strong_count(&items[1]), 1);
+        drop(vec);
+        assert!(items.iter().all(|item| Arc::strong_count(item) == 1));
     }
 
     #[test]
-    fn test_clear() {
-        let mut vec = Vec0::new();
-        vec.push(1);
-        vec.push(2);
-        vec.push(3);
+    fn test_dedup_by_key() {
+        let mut vec = vec0![10, 11, 20, 21, 10];
+        vec.dedup_by_key(|x| *x / 10);
+        assert_eq!(vec.as_slice(), &[10, 20, 10]);
+    }
 
-        vec.clear();
-        assert_eq!(vec.len(), 0);
-        assert!(vec.is_empty());
+    #[test]
+    fn test_eq() {
+        let mut a = vec0![String::from("x")];
+        let b = vec0![String::from("x")];
+        assert_eq!(a, b);
+        a.reserve(100); // capacity doesn't count
+        assert_eq!(a, b);
+        a.push(String::from("y"));
+        assert_ne!(a, b);
+        assert_ne!(Vec0::<i32>::new(), vec0![0]);
     }
 
     #[test]
-    fn test_shrink_to_fit() {
-        let mut vec = Vec0::with_capacity(10);
-        vec.push(1);
-        vec.push(2);
+    fn test_hash() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::collections::HashMap;
+        use std::hash::{Hash, Hasher};
 
-        assert_eq!(vec.capacity(), 10);
-        vec.shrink_to_fit();
-        assert_eq!(vec.capacity(), 2);
-        assert_eq!(vec.len(), 2);
+        fn hash_of<H: Hash + ?Sized>(value: &H) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let a = vec0![1, 2, 3];
+        let b: Vec0<i32> = (1..=3).collect();
+        assert_eq!(hash_of(&a), hash_of(&b));
+        // Same as the slice
+        assert_eq!(hash_of(&a), hash_of(&[1, 2, 3][..]));
+        assert_ne!(hash_of(&a), hash_of(&vec0![3, 2, 1]));
+        // The length prefix keeps split points apart
+        let nested_a = vec0![vec0![1], vec0![2, 3]];
+        let nested_b = vec0![vec0![1, 2], vec0![3]];
+        assert_ne!(hash_of(&nested_a), hash_of(&nested_b));
+
+        let mut map = HashMap::new();
+        map.insert(a, "first");
+        map.insert(vec0![3, 2, 1], "second");
+        assert_eq!(map.get(&b), Some(&"first"));
+        map.insert(b, "replaced");
+        assert_eq!(map.len(), 2);
+        assert_eq!(map[&vec0![1, 2, 3]], "replaced");
+        assert_eq!(map[&vec0![3, 2, 1]], "second");
     }
 
     #[test]
-    fn test_deref_to_slice() {
-        let mut vec = Vec0::new();
-        vec.push(1);
-        vec.push(2);
-        vec.push(3);
+    fn test_from_conversions() {
+        let from_array: Vec0<i32> = [1, 2, 3].into();
+        assert_eq!(from_array.as_slice(), &[1, 2, 3]);
+        let from_vec = Vec0::from(vec![1, 2, 3]);
+        assert_eq!(from_vec, from_array);
+        let from_slice = Vec0::from(&[1, 2, 3][..]);
+        assert_eq!(from_slice, from_array);
 
-        let slice: &[i32] = &vec;
-        assert_eq!(slice.len(), 3);
-        assert_eq!(slice[0], 1);
+        // Empty inputs give empty vectors without allocating
+        let empty: Vec0<String> = [].into();
+        assert!(empty.is_empty());
+        assert_eq!(empty.capacity(), 0);
+        assert!(Vec0::<String>::from(Vec::new()).is_empty());
+        assert!(Vec0::<String>::from(&[][..]).is_empty());
+        assert!(Vec::from(Vec0::<String>::new()).is_empty());
     }
 
     #[test]
-    fn test_into_iter() {
-        let mut vec = Vec0::new();
-        vec.push(1);
-        vec.push(2);
-        vec.push(3);
+    fn test_from_moves_without_cloning() {
+        use std::sync::Arc;
 
-        let mut sum = 0;
-        for val in vec {
-            sum += val;
-        }
-        assert_eq!(sum, 6);
+        let item = Arc::new(0);
+        let std_vec = vec![Arc::clone(&item), Arc::clone(&item)];
+        let vec = Vec0::from(std_vec);
+        assert_eq!(Arc::strong_count(&item), 3);
+
+        let back: Vec<Arc<i32>> = vec.into();
+        assert_eq!(back.len(), 2);
+        assert_eq!(Arc::strong_count(&item), 3);
+
+        let array = [Arc::clone(&item)];
+        let vec = Vec0::from(array);
+        assert_eq!(Arc::strong_count(&item), 4);
+        drop(vec);
+        drop(back);
+        assert_eq!(Arc::strong_count(&item), 1);
     }
 
     #[test]
@@ -716,6 +4292,79 @@ mod tests {
         assert_eq!(vec[1], vec2[1]);
     }
 
+    #[test]
+    fn test_add() {
+        let v1 = vec0![String::from("a"), String::from("b")];
+        let v2 = vec0![String::from("c")];
+
+        // Borrowed operands are cloned into an exactly sized vector
+        let cloned = &v1 + &v2;
+        assert_eq!(cloned.capacity(), 3);
+        assert_eq!(cloned.as_slice(), &["a", "b", "c"]);
+
+        let combined = v1 + v2;
+        assert_eq!(combined.len(), 3);
+        assert_eq!(combined.as_slice(), &["a", "b", "c"]);
+
+        let empty: Vec0<i32> = Vec0::new() + Vec0::new();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_add_moves_without_clone() {
+        // Not Clone: adding owned vectors must move the elements
+        #[derive(Debug, PartialEq)]
+        struct Token(u32);
+
+        let mut vec = vec0![Token(1)] + vec0![Token(2)];
+        vec += vec0![Token(3)];
+        assert_eq!(vec.as_slice(), &[Token(1), Token(2), Token(3)]);
+    }
+
+    #[test]
+    fn test_add_assign() {
+        let mut vec = vec0![1, 2];
+        vec += vec0![3, 4];
+        vec += Vec0::new();
+        assert_eq!(vec.as_slice(), &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    #[allow(clippy::erasing_op)]
+    fn test_mul() {
+        let vec = vec0![String::from("a"), String::from("b")];
+        let repeated = vec.clone() * 3;
+        assert_eq!(repeated.as_slice(), &["a", "b", "a", "b", "a", "b"]);
+        assert_eq!(repeated.capacity(), 6);
+
+        assert_eq!((vec.clone() * 1).as_slice(), vec.as_slice());
+        assert!((vec * 0).is_empty());
+
+        let empty: Vec0<i32> = Vec0::new();
+        assert!((empty * 5).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn test_mul_overflow() {
+        // 2 * usize::MAX elements can't be counted, let alone allocated
+        let _ = vec0![1u8, 2] * usize::MAX;
+    }
+
+    #[test]
+    fn test_mul_assign() {
+        let mut vec = vec0![1, 2, 3];
+        vec *= 2;
+        assert_eq!(vec.as_slice(), &[1, 2, 3, 1, 2, 3]);
+        assert_eq!(vec.capacity(), 6);
+
+        vec *= 1;
+        assert_eq!(vec.len(), 6);
+
+        vec *= 0;
+        assert!(vec.is_empty());
+    }
+
     #[test]
     fn test_debug() {
         let mut vec = Vec0::new();
@@ -815,4 +4464,3 @@ mod tests {
         assert!(v.is_empty());
     }
 }
-
@@ -0,0 +1,176 @@
+//! Integration tests meant to be run under Miri.
+//!
+//! Run with: cargo +nightly miri test --test miri_compat
+//!
+//! These exercise the public `Vec0` and `Box0` APIs through every code path
+//! that touches raw pointers: allocation, reallocation in `grow`, element
+//! shifting in `insert`/`remove`, and deallocation in `Drop`. They also pass
+//! under a normal `cargo test`, Miri just checks them much more strictly.
+
+use rustlib::r#box::Box0;
+use rustlib::vec::Vec0;
+use rustlib::vec0;
+
+// ============================================================================
+// Vec0
+// ============================================================================
+
+#[test]
+fn vec_push_pop_roundtrip() {
+    let mut v = Vec0::new();
+    for i in 0..32 {
+        v.push(i);
+    }
+    for i in (0..32).rev() {
+        assert_eq!(v.pop(), Some(i));
+    }
+    assert_eq!(v.pop(), None);
+}
+
+#[test]
+fn vec_read_write_after_grow_reallocates() {
+    // Every push that hits `len == capacity` calls `grow`, which `realloc`s
+    // and may move the buffer. Reads and writes must go through the new
+    // pointer, never the old one.
+    let mut v = Vec0::new();
+    let mut last_capacity = v.capacity();
+    let mut reallocations = 0;
+
+    for i in 0..64 {
+        v.push(String::from("value") + &i.to_string()); // ptr::write
+        if v.capacity() != last_capacity {
+            reallocations += 1;
+            last_capacity = v.capacity();
+
+            // Touch every element right after the reallocation
+            for j in 0..=i {
+                assert_eq!(v[j], String::from("value") + &j.to_string());
+            }
+        }
+    }
+    assert_eq!(reallocations, 7); // 1, 2, 4, 8, 16, 32, 64
+
+    // ptr::read of elements that were moved by realloc
+    assert_eq!(v.pop(), Some(String::from("value63")));
+    assert_eq!(v.remove(0), String::from("value0"));
+}
+
+#[test]
+fn vec_insert_triggers_grow() {
+    let mut v = Vec0::with_capacity(2);
+    v.push(String::from("a"));
+    v.push(String::from("c"));
+
+    // Full: insert must grow before shifting with ptr::copy
+    v.insert(1, String::from("b"));
+    assert_eq!(v.capacity(), 4);
+    assert_eq!(v.as_slice(), &["a", "b", "c"]);
+
+    v.insert(0, String::from("start"));
+    v.insert(4, String::from("end"));
+    assert_eq!(v.as_slice(), &["start", "a", "b", "c", "end"]);
+}
+
+#[test]
+fn vec_remove_shifts_left() {
+    let mut v = vec0![String::from("a"), String::from("b"), String::from("c")];
+    assert_eq!(v.remove(1), "b");
+    assert_eq!(v.remove(1), "c");
+    assert_eq!(v.remove(0), "a");
+    assert!(v.is_empty());
+}
+
+#[test]
+fn vec_shrink_to_fit_then_push() {
+    let mut v = Vec0::with_capacity(16);
+    v.push(Box::new(1));
+    v.push(Box::new(2));
+    v.shrink_to_fit();
+    assert_eq!(v.capacity(), 2);
+
+    v.push(Box::new(3));
+    assert_eq!(*v[2], 3);
+
+    v.clear();
+    v.shrink_to_fit();
+    assert_eq!(v.capacity(), 0);
+    v.push(Box::new(4));
+    assert_eq!(*v[0], 4);
+}
+
+#[test]
+fn vec_clear_drops_elements() {
+    let mut v = vec0![String::from("x"); 8];
+    v.clear();
+    assert!(v.is_empty());
+    assert_eq!(v.capacity(), 8);
+}
+
+#[test]
+fn vec_clone_is_independent() {
+    let v1 = vec0![String::from("a"), String::from("b")];
+    let mut v2 = v1.clone();
+    v2[0].push('!');
+    assert_eq!(v1[0], "a");
+    assert_eq!(v2[0], "a!");
+}
+
+#[test]
+fn vec_into_iter_partial_consume() {
+    let v = vec0![String::from("a"), String::from("b"), String::from("c")];
+    let mut iter = v.into_iter();
+    assert_eq!(iter.next(), Some(String::from("a")));
+    // Remaining elements are dropped with the iterator
+}
+
+#[test]
+fn vec_deref_slice_ops() {
+    let mut v = vec0![5, 3, 1, 4, 2];
+    v.sort();
+    assert_eq!(v.as_slice(), &[1, 2, 3, 4, 5]);
+    v.as_mut_slice().reverse();
+    assert_eq!(v.as_slice(), &[5, 4, 3, 2, 1]);
+}
+
+// ============================================================================
+// Box0
+// ============================================================================
+
+#[test]
+fn box_new_deref_drop() {
+    let mut b = Box0::new(String::from("hello"));
+    b.push_str(" world");
+    assert_eq!(*b, "hello world");
+}
+
+#[test]
+fn box_into_inner_and_map() {
+    let b = Box0::new(vec![1, 2, 3]);
+    let len = b.map(|v| v.len());
+    assert_eq!(len.into_inner(), 3);
+}
+
+#[test]
+fn box_raw_roundtrip() {
+    let b = Box0::new(String::from("raw"));
+    let ptr = b.into_raw();
+    let b = unsafe { Box0::from_raw(ptr) };
+    assert_eq!(*b, "raw");
+}
+
+#[test]
+fn box_clone_and_nested() {
+    let b1 = Box0::new(Box0::new(String::from("inner")));
+    let b2 = b1.clone();
+    drop(b1);
+    assert_eq!(**b2, "inner");
+}
+
+#[test]
+fn box_clone_zero_sized() {
+    // Cloning a ZST box must not ask the allocator for zero bytes
+    let b1 = Box0::new(());
+    let b2 = b1.clone();
+    drop(b1);
+    assert_eq!(*b2, ());
+}
@@ -0,0 +1,118 @@
+//! Property tests for `Vec0`, checked against `std::vec::Vec`.
+//!
+//! Run with: cargo test --test vec_proptest
+//!
+//! Instead of hand-picked cases, proptest generates random sequences of up to
+//! 1000 `push`/`pop`/`insert`/`remove` calls and checks invariants after each
+//! step. When a property fails it shrinks the sequence to a minimal one that
+//! still fails, which makes the bug much easier to read.
+
+use proptest::prelude::*;
+use rustlib::vec::Vec0;
+
+#[derive(Debug, Clone)]
+enum Op {
+    Push(i32),
+    Pop,
+    // Indices are reduced modulo the current length when applied,
+    // so every generated operation is valid
+    Insert(usize, i32),
+    Remove(usize),
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        any::<i32>().prop_map(Op::Push),
+        Just(Op::Pop),
+        (any::<usize>(), any::<i32>()).prop_map(|(i, x)| Op::Insert(i, x)),
+        any::<usize>().prop_map(Op::Remove),
+    ]
+}
+
+fn ops() -> impl Strategy<Value = Vec<Op>> {
+    prop::collection::vec(op(), 0..=1000)
+}
+
+/// Applies `op` to both vectors and checks they return the same thing.
+fn apply(vec0: &mut Vec0<i32>, vec: &mut Vec<i32>, op: &Op) -> Result<(), TestCaseError> {
+    match *op {
+        Op::Push(x) => {
+            vec0.push(x);
+            vec.push(x);
+        }
+        Op::Pop => prop_assert_eq!(vec0.pop(), vec.pop()),
+        Op::Insert(i, x) => {
+            let index = i % (vec.len() + 1);
+            vec0.insert(index, x);
+            vec.insert(index, x);
+        }
+        Op::Remove(i) => {
+            if !vec.is_empty() {
+                let index = i % vec.len();
+                prop_assert_eq!(vec0.remove(index), vec.remove(index));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn build(ops: &[Op]) -> Vec0<i32> {
+    let mut vec0 = Vec0::new();
+    let mut vec = Vec::new();
+    for op in ops {
+        apply(&mut vec0, &mut vec, op).unwrap();
+    }
+    vec0
+}
+
+proptest! {
+    #[test]
+    fn len_never_exceeds_capacity(ops in ops()) {
+        let mut vec0 = Vec0::new();
+        let mut vec = Vec::new();
+        for op in &ops {
+            apply(&mut vec0, &mut vec, op)?;
+            prop_assert!(vec0.len() <= vec0.capacity());
+        }
+    }
+
+    #[test]
+    fn behaves_like_std_vec(ops in ops()) {
+        let mut vec0 = Vec0::new();
+        let mut vec = Vec::new();
+        for op in &ops {
+            apply(&mut vec0, &mut vec, op)?;
+            prop_assert_eq!(vec0.as_slice(), vec.as_slice());
+        }
+    }
+
+    #[test]
+    fn clone_is_independent(ops in ops(), extra in any::<i32>()) {
+        let original = build(&ops);
+        let snapshot: Vec<i32> = original.as_slice().to_vec();
+
+        let mut copy = original.clone();
+        prop_assert_eq!(copy.as_slice(), original.as_slice());
+
+        // Changing the clone in every possible way leaves the original alone
+        for x in copy.iter_mut() {
+            *x = x.wrapping_add(1);
+        }
+        copy.push(extra);
+        copy.remove(0);
+        prop_assert_eq!(original.as_slice(), snapshot.as_slice());
+
+        drop(original);
+        prop_assert_eq!(copy.len(), snapshot.len());
+    }
+
+    #[test]
+    fn shrink_to_fit_makes_len_equal_capacity(ops in ops()) {
+        let mut vec0 = build(&ops);
+        let before: Vec<i32> = vec0.as_slice().to_vec();
+
+        vec0.shrink_to_fit();
+        prop_assert_eq!(vec0.len(), vec0.capacity());
+        prop_assert_eq!(vec0.as_slice(), before.as_slice());
+    }
+}